@@ -1,9 +1,15 @@
+use std::hash::{Hash, Hasher};
 use std::ops::Index;
+use std::ptr;
 
+use crate::chance::Chance;
+use crate::movedex;
+use crate::moves::{confusion_damage, modify_accuracy, Move, MoveEffect, MoveSlot};
 use crate::pokemon::Pokemon;
 use crate::stats::{Modifier, Stat, StatSet};
+use crate::types::Type;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 pub enum Condition {
     Burned,
     Paralyzed,
@@ -14,10 +20,37 @@ pub enum Condition {
     Poisoned(bool),
 }
 
+/// The version of the game whose mechanics a battle follows.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum Ruleset {
+    /// Red, blue and yellow, including all of their bugs.
+    #[default]
+    Cartridge,
+    /// Pokemon stadium, which fixes some of the bugs.
+    Stadium,
+}
+
+/// Multi-turn move a pokemon is locked into.
+#[derive(Eq, PartialEq, Hash, Copy, Clone)]
+pub(crate) enum Lock {
+    /// Thrash or petal dance, with the number of turns left.
+    Thrash(&'static Move, u8),
+    /// Rage, with the accuracy it will use next turn.
+    Rage(&'static Move, u8),
+    /// Charging a two turn move.
+    Charging(&'static Move),
+    /// Trapping the target, with the number of turns left and the damage of the first hit.
+    ///
+    /// If the trapped pokemon switches out, the trapping move continues on its replacement.
+    Trap(&'static Move, u8, u16),
+}
+
+#[derive(Eq, PartialEq, Hash, Clone)]
 pub struct NonVolatileState<'a> {
     pokemon: &'a Pokemon,
     hit_points: u16,
     condition: Option<Condition>,
+    moves: [Option<MoveSlot>; 4],
 }
 
 impl<'a> NonVolatileState<'a> {
@@ -26,14 +59,107 @@ impl<'a> NonVolatileState<'a> {
             pokemon,
             hit_points: pokemon.get_stat(Stat::HP),
             condition: None,
+            moves: pokemon.moves,
+        }
+    }
+
+    /// Put a state back together from its parts, like a compact copy does.
+    pub(crate) fn from_parts(
+        pokemon: &'a Pokemon,
+        hit_points: u16,
+        condition: Option<Condition>,
+        moves: [Option<MoveSlot>; 4],
+    ) -> Self {
+        NonVolatileState {
+            pokemon,
+            hit_points,
+            condition,
+            moves,
+        }
+    }
+
+    pub fn pokemon(&self) -> &'a Pokemon {
+        self.pokemon
+    }
+
+    pub fn hit_points(&self) -> u16 {
+        self.hit_points
+    }
+
+    pub fn condition(&self) -> Option<Condition> {
+        self.condition
+    }
+
+    /// Get the moves as they are outside of battle, with their PP.
+    pub(crate) fn moves(&self) -> &[Option<MoveSlot>; 4] {
+        &self.moves
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.hit_points > 0
+    }
+
+    /// Turn bad poison into poison.
+    fn cure_bad_poison(&mut self) {
+        if self.condition == Some(Condition::Poisoned(true)) {
+            self.condition = Some(Condition::Poisoned(false));
         }
     }
 }
 
+#[derive(Eq, PartialEq, Hash, Clone)]
 pub struct BattleState<'a> {
     nv_state: NonVolatileState<'a>,
+    /// Species data currently in use. Differs from the actual pokemon after transform.
+    species: &'a Pokemon,
+    types: [Option<Type>; 2],
+    moves: [Option<MoveSlot>; 4],
+    transformed: bool,
+    /// Stats without any stat modifiers applied.
+    raw_stats: StatSet,
     stats: StatSet,
     modifiers: [Modifier; 7],
+    /// Counter for toxic damage. Shared with leech seed.
+    toxic_counter: u16,
+    seeded: bool,
+    /// Turns of confusion remaining.
+    confusion: u8,
+    flinched: bool,
+    must_recharge: bool,
+    /// Hit points of the substitute, if any.
+    substitute: Option<u16>,
+    /// Turns remaining and damage accumulated for bide, if active.
+    bide: Option<(u8, u16)>,
+    lock: Option<Lock>,
+    /// The last move this pokemon used, for mirror move.
+    last_used: Option<&'static Move>,
+    reflect: bool,
+    light_screen: bool,
+    mist: bool,
+    focus_energy: bool,
+    /// The disabled move and the turns remaining, if any.
+    disabled: Option<(&'static Move, u8)>,
+}
+
+/// The parts of a `BattleState` that don't refer to any pokemon or move, for compact copies.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct PlainState {
+    pub(crate) types: [Option<Type>; 2],
+    pub(crate) transformed: bool,
+    pub(crate) raw_stats: StatSet,
+    pub(crate) stats: StatSet,
+    pub(crate) modifiers: [Modifier; 7],
+    pub(crate) toxic_counter: u16,
+    pub(crate) seeded: bool,
+    pub(crate) confusion: u8,
+    pub(crate) flinched: bool,
+    pub(crate) must_recharge: bool,
+    pub(crate) substitute: Option<u16>,
+    pub(crate) bide: Option<(u8, u16)>,
+    pub(crate) reflect: bool,
+    pub(crate) light_screen: bool,
+    pub(crate) mist: bool,
+    pub(crate) focus_energy: bool,
 }
 
 impl<'a> BattleState<'a> {
@@ -42,58 +168,2424 @@ impl<'a> BattleState<'a> {
         BattleState::restore(nv_state)
     }
 
+    /// Put a state back together from its parts, like a compact copy does.
+    pub(crate) fn from_parts(
+        nv_state: NonVolatileState<'a>,
+        species: &'a Pokemon,
+        moves: [Option<MoveSlot>; 4],
+        lock: Option<Lock>,
+        last_used: Option<&'static Move>,
+        disabled: Option<(&'static Move, u8)>,
+        plain: PlainState,
+    ) -> Self {
+        BattleState {
+            nv_state,
+            species,
+            types: plain.types,
+            moves,
+            transformed: plain.transformed,
+            raw_stats: plain.raw_stats,
+            stats: plain.stats,
+            modifiers: plain.modifiers,
+            toxic_counter: plain.toxic_counter,
+            seeded: plain.seeded,
+            confusion: plain.confusion,
+            flinched: plain.flinched,
+            must_recharge: plain.must_recharge,
+            substitute: plain.substitute,
+            bide: plain.bide,
+            lock,
+            last_used,
+            reflect: plain.reflect,
+            light_screen: plain.light_screen,
+            mist: plain.mist,
+            focus_energy: plain.focus_energy,
+            disabled,
+        }
+    }
+
+    /// Get the parts of the state that don't refer to any pokemon or move.
+    pub(crate) fn plain(&self) -> PlainState {
+        PlainState {
+            types: self.types,
+            transformed: self.transformed,
+            raw_stats: self.raw_stats,
+            stats: self.stats,
+            modifiers: self.modifiers,
+            toxic_counter: self.toxic_counter,
+            seeded: self.seeded,
+            confusion: self.confusion,
+            flinched: self.flinched,
+            must_recharge: self.must_recharge,
+            substitute: self.substitute,
+            bide: self.bide,
+            reflect: self.reflect,
+            light_screen: self.light_screen,
+            mist: self.mist,
+            focus_energy: self.focus_energy,
+        }
+    }
+
+    /// Get the state that stays when switching out.
+    pub(crate) fn nv_state(&self) -> &NonVolatileState<'a> {
+        &self.nv_state
+    }
+
+    pub(crate) fn lock(&self) -> Option<&Lock> {
+        self.lock.as_ref()
+    }
+
+    /// Get the disabled move and the turns remaining, if any.
+    pub(crate) fn disabled(&self) -> Option<(&'static Move, u8)> {
+        self.disabled
+    }
+
     pub fn restore(mut nv_state: NonVolatileState<'a>) -> Self {
-        // Turn bad poison into poison.
-        nv_state.condition = match nv_state.condition {
-            Some(Condition::Poisoned(true)) => Some(Condition::Poisoned(false)),
-            x => x,
-        };
+        nv_state.cure_bad_poison();
 
         // Recompute all stats.
+        let pokemon = nv_state.pokemon;
+        let moves = nv_state.moves;
         let stats = [
-            nv_state.pokemon.get_stat(Stat::HP),
-            nv_state.pokemon.get_stat(Stat::Attack),
-            nv_state.pokemon.get_stat(Stat::Defense),
-            nv_state.pokemon.get_stat(Stat::Special),
-            nv_state.pokemon.get_stat(Stat::Speed),
+            pokemon.get_stat(Stat::HP),
+            pokemon.get_stat(Stat::Attack),
+            pokemon.get_stat(Stat::Defense),
+            pokemon.get_stat(Stat::Special),
+            pokemon.get_stat(Stat::Speed),
         ];
 
-        BattleState {
+        let mut state = BattleState {
             nv_state,
+            species: pokemon,
+            types: pokemon.types,
+            moves,
+            transformed: false,
+            raw_stats: stats,
             stats,
             modifiers: Default::default(),
+            toxic_counter: 0,
+            seeded: false,
+            confusion: 0,
+            flinched: false,
+            must_recharge: false,
+            substitute: None,
+            bide: None,
+            lock: None,
+            last_used: None,
+            reflect: false,
+            light_screen: false,
+            mist: false,
+            focus_energy: false,
+            disabled: None,
+        };
+
+        // A pokemon that is switched in while paralyzed or burned suffers the drop again.
+        state.apply_status_drop();
+        state
+    }
+
+    /// Switch the pokemon out, dropping all volatile state.
+    ///
+    /// The PP used is kept, unless the pokemon transformed. A mimicked move reverts to mimic, but
+    /// keeps its PP.
+    pub fn switch_out(self) -> NonVolatileState<'a> {
+        let mut nv_state = self.nv_state;
+        nv_state.cure_bad_poison();
+
+        if !self.transformed {
+            for (kept, used) in nv_state.moves.iter_mut().zip(&self.moves) {
+                if let (Some(kept), Some(used)) = (kept.as_mut(), used) {
+                    let original = kept.known();
+                    *kept = *used;
+                    kept.replace(original);
+                }
+            }
         }
+
+        nv_state
     }
 
     pub fn is_alive(&self) -> bool {
         self.nv_state.hit_points > 0
     }
 
+    /// Deal damage from an attack to this pokemon.
+    ///
+    /// If the pokemon has a substitute, the substitute takes the damage instead. Any damage in
+    /// excess of what is needed to break the substitute is lost. Returns the remaining hit
+    /// points of the pokemon itself.
     pub fn damage(&mut self, damage: u16) -> u16 {
+        match self.substitute {
+            Some(hp) if hp > damage => self.substitute = Some(hp - damage),
+            Some(_) => self.substitute = None,
+            None => return self.damage_direct(damage),
+        }
+
+        self.nv_state.hit_points
+    }
+
+    /// Deal damage to this pokemon, bypassing any substitute.
+    ///
+    /// Used for recoil, residual damage and other damage that doesn't come from an attack.
+    pub fn damage_direct(&mut self, damage: u16) -> u16 {
         self.nv_state.hit_points = self.nv_state.hit_points.saturating_sub(damage);
         self.nv_state.hit_points
     }
 
+    /// Restore hit points, up to the maximum HP of the pokemon.
+    ///
+    /// Like `damage`, this returns the remaining hit points.
+    pub fn heal(&mut self, amount: u16) -> u16 {
+        let max = self[Stat::HP];
+        self.nv_state.hit_points = self.nv_state.hit_points.saturating_add(amount).min(max);
+        self.nv_state.hit_points
+    }
+
+    fn recovery_fails(&self) -> bool {
+        recovery_fails(self[Stat::HP], self.nv_state.hit_points)
+    }
+
+    /// Use recover or soft-boiled, healing half of the maximum HP.
+    ///
+    /// Returns whether the move succeeded.
+    pub fn recover(&mut self) -> bool {
+        if self.recovery_fails() {
+            false
+        } else {
+            self.heal(self[Stat::HP] / 2);
+            true
+        }
+    }
+
+    /// Use rest, fully healing and curing any status, but putting the pokemon to sleep.
+    ///
+    /// Returns whether the move succeeded.
+    pub fn rest(&mut self) -> bool {
+        if self.recovery_fails() {
+            false
+        } else {
+            self.heal(self[Stat::HP]);
+            self.nv_state.condition = Some(Condition::Asleep(2));
+            true
+        }
+    }
+
+    /// Try to inflict a non-volatile status condition.
+    ///
+    /// This fails if the pokemon already has a condition, or if its type makes it immune.
+    /// Returns whether the condition was inflicted.
+    pub fn inflict(&mut self, condition: Condition) -> bool {
+        if self.nv_state.condition.is_some() {
+            return false;
+        }
+
+        let immune_type = match condition {
+            Condition::Burned => Some(Type::Fire),
+            Condition::Frozen => Some(Type::Ice),
+            Condition::Poisoned(_) => Some(Type::Poison),
+            _ => None,
+        };
+
+        if immune_type.is_some_and(|t| self.has_type(t)) {
+            return false;
+        }
+
+        if condition == Condition::Poisoned(true) {
+            self.toxic_counter = 0;
+        }
+
+        self.nv_state.condition = Some(condition);
+        self.apply_status_drop();
+        true
+    }
+
+    /// Apply the stat drop for the current status condition.
+    ///
+    /// Paralysis quarters speed and burn halves attack. The drop is applied to the current stats,
+    /// so applying it again stacks. RBY does exactly that to the opponent of a pokemon that
+    /// successfully changes a stat stage, while changing a stat of the paralyzed or burned
+    /// pokemon itself recomputes it and loses the drop.
+    pub fn apply_status_drop(&mut self) {
+        if let Some((stat, divider)) = self.status_drop() {
+            let current = &mut self.stats[usize::from(stat)];
+            *current = (*current / divider).max(1);
+        }
+    }
+
+    /// Get the stat lowered by the current status condition, and what it is divided by.
+    fn status_drop(&self) -> Option<(Stat, u16)> {
+        match self.nv_state.condition {
+            Some(Condition::Paralyzed) => Some((Stat::Speed, 4)),
+            Some(Condition::Burned) => Some((Stat::Attack, 2)),
+            _ => None,
+        }
+    }
+
+    /// Try to seed this pokemon with leech seed.
+    ///
+    /// Grass pokemon are immune, and a pokemon can't be seeded twice.
+    pub fn seed(&mut self) -> bool {
+        if self.seeded || self.has_type(Type::Grass) {
+            false
+        } else {
+            self.seeded = true;
+            true
+        }
+    }
+
+    pub fn is_seeded(&self) -> bool {
+        self.seeded
+    }
+
+    /// Compute a single portion of residual damage.
+    ///
+    /// Normally this is 1/16th of the max HP. When badly poisoned, it is multiplied by the toxic
+    /// counter, which is incremented first. RBY uses the same routine for poison, burn and
+    /// leech seed, so a badly poisoned pokemon that is also seeded increments the counter twice
+    /// per turn and takes escalating leech seed damage.
+    pub(crate) fn residual_portion(&mut self) -> u16 {
+        let mut damage = (self[Stat::HP] / 16).max(1);
+
+        if self.nv_state.condition == Some(Condition::Poisoned(true)) {
+            self.toxic_counter += 1;
+            damage *= self.toxic_counter;
+        }
+
+        damage
+    }
+
+    /// Apply residual damage from burn, poison and leech seed.
+    ///
+    /// Leech seed damage is restored to the opponent. Returns the total damage taken.
+    pub fn apply_residual(&mut self, opponent: &mut BattleState) -> u16 {
+        let before = self.nv_state.hit_points;
+
+        match self.nv_state.condition {
+            Some(Condition::Burned) | Some(Condition::Poisoned(_)) => {
+                let damage = self.residual_portion();
+                self.damage_direct(damage);
+            }
+            _ => (),
+        }
+
+        if self.seeded && self.is_alive() {
+            let damage = self.residual_portion().min(self.nv_state.hit_points);
+            self.damage_direct(damage);
+            opponent.heal(damage);
+        }
+
+        before - self.nv_state.hit_points
+    }
+
+    /// Try to confuse this pokemon for 2 to 5 turns.
+    ///
+    /// Fails if the pokemon is already confused.
+    pub fn confuse(&mut self, rand: &mut impl Chance) -> bool {
+        if self.is_confused() {
+            false
+        } else {
+            self.confusion = rand.range(2, 6);
+            true
+        }
+    }
+
+    pub fn is_confused(&self) -> bool {
+        self.confusion > 0
+    }
+
+    /// Check confusion before the pokemon tries to move.
+    ///
+    /// This counts down the confusion, and returns true if the pokemon hurts itself instead of
+    /// moving. Use `moves::confusion_damage` to compute the damage it takes.
+    pub fn check_confusion(&mut self, rand: &mut impl Chance) -> bool {
+        if !self.is_confused() {
+            return false;
+        }
+
+        self.confusion -= 1;
+        self.is_confused() && rand.coin()
+    }
+
+    /// Make this pokemon flinch.
+    ///
+    /// As in RBY, flinching also cancels a pending hyper beam recharge, even though the flinch
+    /// itself only matters if the pokemon has not moved yet this turn.
+    pub fn flinch(&mut self) {
+        self.flinched = true;
+        self.must_recharge = false;
+    }
+
+    pub fn has_flinched(&self) -> bool {
+        self.flinched
+    }
+
+    pub fn must_recharge(&self) -> bool {
+        self.must_recharge
+    }
+
+    /// Create a substitute, costing a quarter of the maximum HP.
+    ///
+    /// As in RBY, this only fails when the pokemon has less HP than the cost, so a pokemon with
+    /// exactly a quarter of its HP left will faint. Returns whether a substitute was created.
+    pub fn create_substitute(&mut self) -> bool {
+        let cost = self[Stat::HP] / 4;
+        if self.substitute.is_some() || self.nv_state.hit_points < cost {
+            false
+        } else {
+            self.damage_direct(cost);
+            self.substitute = Some(cost);
+            true
+        }
+    }
+
+    pub fn has_substitute(&self) -> bool {
+        self.substitute.is_some()
+    }
+
+    pub fn is_biding(&self) -> bool {
+        self.bide.is_some()
+    }
+
+    /// Check whether this pokemon is unable to choose its own action.
+    ///
+    /// This is the case while recharging, biding, thrashing or raging. The action given to
+    /// `Battle::turn` for a locked pokemon is ignored.
+    pub fn is_locked(&self) -> bool {
+        self.must_recharge || self.bide.is_some() || self.lock.is_some()
+    }
+
+    /// Check whether this pokemon can't be hit, because it is in the middle of fly or dig.
+    pub fn is_invulnerable(&self) -> bool {
+        match self.lock {
+            Some(Lock::Charging(charging)) => charging.effect() == MoveEffect::Charge(true),
+            _ => false,
+        }
+    }
+
+    /// Get the last move this pokemon used.
+    pub fn last_used(&self) -> Option<&'static Move> {
+        self.last_used
+    }
+
+    /// Put up reflect. Fails if it is already up.
+    pub fn use_reflect(&mut self) -> bool {
+        !std::mem::replace(&mut self.reflect, true)
+    }
+
+    pub fn has_reflect(&self) -> bool {
+        self.reflect
+    }
+
+    /// Put up light screen. Fails if it is already up.
+    pub fn use_light_screen(&mut self) -> bool {
+        !std::mem::replace(&mut self.light_screen, true)
+    }
+
+    pub fn has_light_screen(&self) -> bool {
+        self.light_screen
+    }
+
+    /// Shroud this pokemon in mist. Fails if it already is.
+    pub fn use_mist(&mut self) -> bool {
+        !std::mem::replace(&mut self.mist, true)
+    }
+
+    pub fn has_mist(&self) -> bool {
+        self.mist
+    }
+
+    /// Start focusing energy. Fails if the pokemon is already focused.
+    ///
+    /// Dire hit sets the same flag, so this is also how the item should be used.
+    pub fn use_focus_energy(&mut self) -> bool {
+        !std::mem::replace(&mut self.focus_energy, true)
+    }
+
+    pub fn has_focus_energy(&self) -> bool {
+        self.focus_energy
+    }
+
+    /// Disable a random known move for 1 to 8 turns.
+    ///
+    /// Fails if a move is already disabled. Returns the disabled move, if any.
+    pub fn disable(&mut self, rand: &mut impl Chance) -> Option<&'static Move> {
+        if self.disabled.is_some() {
+            return None;
+        }
+
+        let known: Vec<_> = self
+            .moves
+            .iter()
+            .flatten()
+            .filter(|slot| slot.pp() > 0)
+            .map(MoveSlot::known)
+            .collect();
+        let &disabled = rand.pick(&known)?;
+        self.disabled = Some((disabled, rand.range(1, 9)));
+        Some(disabled)
+    }
+
+    /// Check whether the given move is currently disabled.
+    pub fn is_disabled(&self, used: &Move) -> bool {
+        self.disabled.is_some_and(|(d, _)| ptr::eq(d, used))
+    }
+
+    /// Count down the disable, returning true if it just wore off.
+    fn tick_disable(&mut self) -> bool {
+        match self.disabled {
+            Some((_, 1)) => {
+                self.disabled = None;
+                true
+            }
+            Some((disabled, turns)) => {
+                self.disabled = Some((disabled, turns - 1));
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Change the types of this pokemon to those of the target.
+    pub fn convert(&mut self, target: &BattleState) {
+        self.types = target.types;
+    }
+
+    /// Undo the effects of haze on this side.
+    ///
+    /// This resets all stat stages and removes reflect, light screen, focus energy, mist,
+    /// leech seed, disable and confusion. Bad poison becomes regular poison.
+    pub fn haze(&mut self) {
+        self.modifiers = Default::default();
+        self.stats = self.raw_stats;
+        self.reflect = false;
+        self.light_screen = false;
+        self.focus_energy = false;
+        self.mist = false;
+        self.seeded = false;
+        self.disabled = None;
+        self.confusion = 0;
+        self.nv_state.cure_bad_poison();
+    }
+
+    pub fn hit_points(&self) -> u16 {
+        self.nv_state.hit_points
+    }
+
+    pub fn condition(&self) -> Option<Condition> {
+        self.nv_state.condition
+    }
+
+    pub fn set_condition(&mut self, condition: Option<Condition>) {
+        self.nv_state.condition = condition;
+    }
+
     pub fn get_modifier(&self, stat: Stat) -> Modifier {
         self.modifiers[usize::from(stat)]
     }
 
+    /// Get a stat without stat stages or status drops applied, as used by critical hits.
+    pub fn raw_stat(&self, stat: Stat) -> u16 {
+        match stat {
+            Stat::Accuracy | Stat::Evasion => unimplemented!(),
+            stat => self.raw_stats[usize::from(stat)],
+        }
+    }
+
+    /// Change the stage of a stat by the given amount of levels, and recompute the stat.
+    ///
+    /// Like on the cartridge, the recomputed stat loses any status drop. Returns false if the stat
+    /// can't go any further in that direction.
+    pub fn modify_stat(&mut self, stat: Stat, change: i8) -> bool {
+        let current = self.get_modifier(stat);
+        let modifier = current + change;
+        if modifier == current {
+            return false;
+        }
+
+        self.modifiers[usize::from(stat)] = modifier;
+        match stat {
+            Stat::Accuracy | Stat::Evasion => (),
+            stat => {
+                let raw = self.raw_stats[usize::from(stat)];
+                self.stats[usize::from(stat)] = modifier.modify(raw);
+            }
+        }
+
+        true
+    }
+
+    /// Change a stat stage in battle, following the ruleset.
+    ///
+    /// On the cartridge, the recomputed stat loses the status drop. Stadium keeps it.
+    fn change_stat(&mut self, stat: Stat, change: i8, ruleset: Ruleset) -> bool {
+        if !self.modify_stat(stat, change) {
+            return false;
+        }
+
+        if ruleset == Ruleset::Stadium && self.status_drop().map(|(s, _)| s) == Some(stat) {
+            self.apply_status_drop();
+        }
+        true
+    }
+
     pub fn get_modifier_mut(&mut self, stat: Stat) -> &mut Modifier {
         &mut self.modifiers[usize::from(stat)]
     }
 
-    pub fn pokemon(&self) -> &Pokemon {
+    /// Get the actual pokemon in battle.
+    pub fn pokemon(&self) -> &'a Pokemon {
         self.nv_state.pokemon
     }
+
+    /// Get the species data in use, which is the transformed species after transform.
+    pub fn species(&self) -> &'a Pokemon {
+        self.species
+    }
+
+    /// Check if this pokemon currently has the wanted type.
+    pub fn has_type(&self, wanted: Type) -> bool {
+        self.types.contains(&Some(wanted))
+    }
+
+    pub fn get_types(&self) -> &[Option<Type>] {
+        &self.types
+    }
+
+    /// Get the moves this pokemon currently knows.
+    pub fn moves(&self) -> &[Option<MoveSlot>; 4] {
+        &self.moves
+    }
+
+    /// Check whether this pokemon has to use struggle, because all of its moves are out of PP.
+    ///
+    /// As in RBY, a disabled move with PP left still counts.
+    pub fn must_struggle(&self) -> bool {
+        self.moves.iter().any(Option::is_some) && self.moves.iter().flatten().all(|s| s.pp() == 0)
+    }
+
+    /// Get the moves this pokemon can choose from this turn.
+    ///
+    /// Moves without PP left or that are disabled can't be chosen, and struggle is only used when
+    /// all moves are out of PP. So, when the only move with PP left is disabled, RBY lets the
+    /// pokemon choose its moves without PP instead, and their PP underflows when used.
+    pub fn available_moves(&self) -> Vec<&'static Move> {
+        if self.must_struggle() {
+            return vec![Move::fallback()];
+        }
+
+        let known = self
+            .moves
+            .iter()
+            .flatten()
+            .map(MoveSlot::known)
+            .filter(|&m| !self.is_disabled(m));
+        let with_pp: Vec<_> = self
+            .moves
+            .iter()
+            .flatten()
+            .filter(|s| s.pp() > 0 && !self.is_disabled(s.known()))
+            .map(MoveSlot::known)
+            .collect();
+
+        if !with_pp.is_empty() {
+            with_pp
+        } else if known.clone().next().is_some() {
+            known.collect()
+        } else {
+            vec![Move::fallback()]
+        }
+    }
+
+    /// Use up a PP of the given move, if the pokemon knows it.
+    fn use_pp(&mut self, used: &Move) {
+        let slot = self
+            .moves
+            .iter_mut()
+            .flatten()
+            .find(|s| ptr::eq(s.known(), used));
+
+        if let Some(slot) = slot {
+            slot.use_pp();
+        }
+    }
+
+    /// Transform into the target.
+    ///
+    /// This copies the species, types, moves, stat modifiers and current stats of the target,
+    /// except for HP. As in RBY, the stats are copied as they currently are, so any boosts or
+    /// drops carry over.
+    pub fn transform(&mut self, target: &BattleState<'a>) {
+        self.species = target.species;
+        self.types = target.types;
+        self.transformed = true;
+        // The copied moves get 5 PP each.
+        self.moves = target.moves.map(|slot| {
+            slot.map(|slot| {
+                let mut copied = MoveSlot::new(slot.known());
+                copied.set_pp(5);
+                copied
+            })
+        });
+        self.modifiers = target.modifiers;
+
+        let hp = usize::from(Stat::HP);
+        for i in (hp + 1)..self.stats.len() {
+            self.raw_stats[i] = target.raw_stats[i];
+            self.stats[i] = target.stats[i];
+        }
+    }
+
+    /// Replace mimic in the moveset with the given move, which keeps the PP of mimic.
+    ///
+    /// Returns false if the pokemon doesn't know mimic.
+    pub fn mimic(&mut self, mimicked: &'static Move) -> bool {
+        let slot = self
+            .moves
+            .iter_mut()
+            .flatten()
+            .find(|s| s.known().effect() == MoveEffect::Mimic);
+
+        match slot {
+            Some(slot) => {
+                slot.replace(mimicked);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
-impl<'a> Index<Stat> for BattleState<'a> {
-    type Output = u16;
+/// Action a side can take during a turn.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Action {
+    UseMove(&'static Move),
+    /// Switch to the team member with the given index.
+    Switch(usize),
+}
 
-    fn index(&self, index: Stat) -> &Self::Output {
-        match index {
-            Stat::Accuracy | Stat::Evasion => unimplemented!(),
-            x => &self.stats[usize::from(x)],
+/// Things that can happen during a turn.
+///
+/// Sides are identified by their index in the battle, 0 or 1.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Event {
+    UsedMove(usize, &'static str),
+    Missed(usize),
+    Failed(usize),
+    CriticalHit(usize),
+    Damaged(usize, u16),
+    Recoil(usize, u16),
+    Healed(usize, u16),
+    Residual(usize, u16),
+    Inflicted(usize, Condition),
+    Seeded(usize),
+    Confused(usize),
+    HurtItself(usize, u16),
+    Flinched(usize),
+    Substitute(usize),
+    SubstituteDamaged(usize, u16),
+    SubstituteBroke(usize),
+    StatChanged(usize, Stat, i8),
+    Charging(usize),
+    Transformed(usize),
+    Mimicked(usize, &'static str),
+    Disabled(usize, &'static str),
+    /// The pokemon tried to use its disabled move.
+    MoveDisabled(usize),
+    DisableEnded(usize),
+    Converted(usize),
+    Hazed,
+    Reflect(usize),
+    LightScreen(usize),
+    Mist(usize),
+    FocusEnergy(usize),
+    /// The pokemon was hit the given number of times.
+    Hits(usize, u8),
+    StoringEnergy(usize),
+    UnleashedEnergy(usize),
+    Recharging(usize),
+    FastAsleep(usize),
+    WokeUp(usize),
+    FrozenSolid(usize),
+    Thawed(usize),
+    FullyParalyzed(usize),
+    /// The pokemon can't move, because it is trapped by the opponent.
+    Trapped(usize),
+    Fainted(usize),
+    /// The side sent out the team member with the given index.
+    SwitchedIn(usize, usize),
+}
+
+/// Check whether a recovery move would fail at the given HP.
+///
+/// Recovery fails at full health, but RBY also compares only the low byte of the missing HP, so
+/// it also fails when exactly 255 or 511 HP is missing.
+pub(crate) fn recovery_fails(max: u16, hit_points: u16) -> bool {
+    let missing = max - hit_points;
+    missing & 0xff == 0xff || missing == 0
+}
+
+/// Get the states for the given side and its opponent, respectively.
+fn split<'b, 'a>(
+    sides: &'b mut [BattleState<'a>; 2],
+    side: usize,
+) -> (&'b mut BattleState<'a>, &'b mut BattleState<'a>) {
+    let (first, second) = sides.split_at_mut(1);
+    if side == 0 {
+        (&mut first[0], &mut second[0])
+    } else {
+        (&mut second[0], &mut first[0])
+    }
+}
+
+/// Pick a random move for metronome, excluding metronome itself and struggle.
+fn metronome(rand: &mut impl Chance) -> &'static Move {
+    let callable: Vec<_> = movedex::MOVES
+        .iter()
+        .copied()
+        .filter(|called| called.effect() != MoveEffect::Metronome)
+        .filter(|&called| !ptr::eq(called, Move::fallback()))
+        .collect();
+    rand.pick(&callable).expect("No moves to call")
+}
+
+/// A battle between two teams of pokemon.
+///
+/// Battles compare equal if they are in the same state, so they can be used to memoize results.
+#[derive(Clone)]
+pub struct Battle<'a> {
+    sides: [BattleState<'a>; 2],
+    /// The teams of both sides. The entry for the active pokemon is only updated when it
+    /// switches out.
+    teams: [Vec<NonVolatileState<'a>>; 2],
+    /// Index of the active pokemon in the team of each side.
+    active: [usize; 2],
+    /// The last damage dealt by any attack, by either side.
+    last_damage: u16,
+    /// The last move used by either side.
+    last_move: Option<&'static Move>,
+    ruleset: Ruleset,
+}
+
+impl<'a> Battle<'a> {
+    pub fn new(first: BattleState<'a>, second: BattleState<'a>) -> Self {
+        Battle::with_ruleset(first, second, Ruleset::default())
+    }
+
+    pub fn with_ruleset(first: BattleState<'a>, second: BattleState<'a>, ruleset: Ruleset) -> Self {
+        Battle {
+            teams: [vec![first.nv_state.clone()], vec![second.nv_state.clone()]],
+            active: [0, 0],
+            sides: [first, second],
+            last_damage: 0,
+            last_move: None,
+            ruleset,
+        }
+    }
+
+    /// Put a battle back together from its parts, like a compact copy does.
+    pub(crate) fn from_parts(
+        sides: [BattleState<'a>; 2],
+        teams: [Vec<NonVolatileState<'a>>; 2],
+        active: [usize; 2],
+        last_damage: u16,
+        last_move: Option<&'static Move>,
+        ruleset: Ruleset,
+    ) -> Self {
+        Battle {
+            sides,
+            teams,
+            active,
+            last_damage,
+            last_move,
+            ruleset,
+        }
+    }
+
+    /// Create a battle between two teams, starting with the first pokemon of each team.
+    ///
+    /// Panics if either team is empty.
+    pub fn with_teams(first: &'a [Pokemon], second: &'a [Pokemon], ruleset: Ruleset) -> Self {
+        let team = |pokemon: &'a [Pokemon]| -> Vec<_> {
+            pokemon.iter().map(NonVolatileState::new).collect()
+        };
+        let teams = [team(first), team(second)];
+
+        Battle {
+            sides: [
+                BattleState::restore(teams[0][0].clone()),
+                BattleState::restore(teams[1][0].clone()),
+            ],
+            teams,
+            active: [0, 0],
+            last_damage: 0,
+            last_move: None,
+            ruleset,
+        }
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        self.ruleset
+    }
+
+    /// Get the last damage dealt in this battle.
+    ///
+    /// In RBY, this is a single value shared between both sides, which is what counter uses.
+    pub fn last_damage(&self) -> u16 {
+        self.last_damage
+    }
+
+    /// Get the last move used in this battle, by either side.
+    pub fn last_move(&self) -> Option<&'static Move> {
+        self.last_move
+    }
+
+    pub fn side(&self, side: usize) -> &BattleState<'a> {
+        &self.sides[side]
+    }
+
+    pub fn side_mut(&mut self, side: usize) -> &mut BattleState<'a> {
+        &mut self.sides[side]
+    }
+
+    /// Get the index of the active pokemon of a side in its team.
+    pub fn active(&self, side: usize) -> usize {
+        self.active[side]
+    }
+
+    pub fn team_size(&self, side: usize) -> usize {
+        self.teams[side].len()
+    }
+
+    /// Get the non-volatile state of a team member, including the active pokemon.
+    pub fn member(&self, side: usize, index: usize) -> &NonVolatileState<'a> {
+        if index == self.active[side] {
+            &self.sides[side].nv_state
+        } else {
+            &self.teams[side][index]
         }
     }
+
+    /// Count the pokemon of a side that haven't fainted.
+    pub fn remaining(&self, side: usize) -> usize {
+        (0..self.team_size(side))
+            .filter(|&i| self.member(side, i).is_alive())
+            .count()
+    }
+
+    /// Check whether the battle is over, meaning either side has no pokemon left.
+    pub fn is_over(&self) -> bool {
+        (0..2).any(|side| self.remaining(side) == 0)
+    }
+
+    /// Get the winning side, if the battle is over and it isn't a draw.
+    pub fn winner(&self) -> Option<usize> {
+        match (self.remaining(0), self.remaining(1)) {
+            (0, 0) => None,
+            (_, 0) => Some(0),
+            (0, _) => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Check whether a side has to send out a new pokemon before the next turn.
+    pub fn must_replace(&self, side: usize) -> bool {
+        !self.sides[side].is_alive() && self.remaining(side) > 0
+    }
+
+    /// Check whether a side can switch to the given team member.
+    pub fn can_switch(&self, side: usize, index: usize) -> bool {
+        index != self.active[side]
+            && index < self.team_size(side)
+            && self.teams[side][index].is_alive()
+    }
+
+    /// Get all actions a side can choose from.
+    ///
+    /// A side whose pokemon fainted can only switch. A pokemon that is locked into a move, like
+    /// thrash or the recharge turn of hyper beam, can't switch and has a single action.
+    pub fn available_actions(&self, side: usize) -> Vec<Action> {
+        let switches = (0..self.team_size(side))
+            .filter(|&i| self.can_switch(side, i))
+            .map(Action::Switch);
+
+        let state = &self.sides[side];
+        if !state.is_alive() {
+            return switches.collect();
+        }
+
+        if state.is_locked() {
+            // The action is ignored, but the locked move is the most sensible one to report.
+            let locked = state.last_used().unwrap_or_else(Move::fallback);
+            return vec![Action::UseMove(locked)];
+        }
+
+        state
+            .available_moves()
+            .into_iter()
+            .map(Action::UseMove)
+            .chain(switches)
+            .collect()
+    }
+
+    /// Send out a new pokemon, outside of a turn, after the active one has fainted.
+    ///
+    /// Panics if the side can't switch to that pokemon.
+    pub fn replace(&mut self, side: usize, index: usize) -> Event {
+        // A trapping move ends when its target faints.
+        if let Some(Lock::Trap(_, _, _)) = self.sides[1 - side].lock {
+            self.sides[1 - side].lock = None;
+        }
+
+        self.switch(side, index)
+    }
+
+    /// Switch the active pokemon of a side with a team member.
+    fn switch(&mut self, side: usize, index: usize) -> Event {
+        assert!(self.can_switch(side, index), "Invalid switch");
+
+        let incoming = BattleState::restore(self.teams[side][index].clone());
+        let outgoing = std::mem::replace(&mut self.sides[side], incoming);
+        self.teams[side][self.active[side]] = outgoing.switch_out();
+        self.active[side] = index;
+
+        Event::SwitchedIn(side, index)
+    }
+
+    /// Determine which side moves first.
+    ///
+    /// Switches always go first. Moves with a higher priority go first. Otherwise the faster
+    /// pokemon moves first, speed ties are decided randomly.
+    fn move_order(&self, rand: &mut impl Chance, actions: &[Action; 2]) -> [usize; 2] {
+        let key = |side: usize| match actions[side] {
+            Action::UseMove(used) => (false, used.priority(), self.sides[side][Stat::Speed]),
+            Action::Switch(_) => (true, 0, self.sides[side][Stat::Speed]),
+        };
+        let keys = [key(0), key(1)];
+
+        if keys[0] > keys[1] || (keys[0] == keys[1] && rand.coin()) {
+            [0, 1]
+        } else {
+            [1, 0]
+        }
+    }
+
+    /// Play out a single turn, given the actions for both sides.
+    ///
+    /// Returns the events that happened during the turn, in order.
+    pub fn turn(&mut self, rand: &mut impl Chance, actions: [Action; 2]) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        for &side in &self.move_order(rand, &actions) {
+            if self.sides.iter().any(|s| !s.is_alive()) {
+                break;
+            }
+
+            let used = match actions[side] {
+                Action::UseMove(used) => used,
+                Action::Switch(index) => {
+                    events.push(self.switch(side, index));
+                    continue;
+                }
+            };
+            self.act(rand, side, used, &mut events);
+
+            // RBY applies residual damage right after the pokemon's own move.
+            let (user, opponent) = split(&mut self.sides, side);
+            if user.is_alive() && opponent.is_alive() {
+                let damage = user.apply_residual(opponent);
+                if damage > 0 {
+                    events.push(Event::Residual(side, damage));
+
+                    // RBY quirk: residual damage also counts towards bide.
+                    if let Some((turns, total)) = user.bide {
+                        user.bide = Some((turns, total.saturating_add(damage)));
+                    }
+                }
+            }
+
+            for (i, state) in self.sides.iter().enumerate() {
+                if !state.is_alive() {
+                    events.push(Event::Fainted(i));
+                }
+            }
+        }
+
+        for state in &mut self.sides {
+            state.flinched = false;
+        }
+
+        events
+    }
+
+    /// Let the given side act, if it is able to.
+    fn act(
+        &mut self,
+        rand: &mut impl Chance,
+        side: usize,
+        used: &'static Move,
+        events: &mut Vec<Event>,
+    ) {
+        let (user, opponent) = split(&mut self.sides, side);
+
+        match user.condition() {
+            // The turn the pokemon wakes up is lost as well.
+            Some(Condition::Asleep(turns)) if turns > 1 => {
+                user.set_condition(Some(Condition::Asleep(turns - 1)));
+                events.push(Event::FastAsleep(side));
+                return;
+            }
+            Some(Condition::Asleep(_)) => {
+                user.set_condition(None);
+                events.push(Event::WokeUp(side));
+                return;
+            }
+            Some(Condition::Frozen) => {
+                events.push(Event::FrozenSolid(side));
+                return;
+            }
+            _ => (),
+        }
+
+        if let Some(Lock::Trap(_, _, _)) = opponent.lock {
+            events.push(Event::Trapped(side));
+            return;
+        }
+
+        if user.must_recharge {
+            user.must_recharge = false;
+            events.push(Event::Recharging(side));
+            return;
+        }
+
+        if user.flinched {
+            events.push(Event::Flinched(side));
+            return;
+        }
+
+        if user.check_confusion(rand) {
+            let damage = confusion_damage(user);
+            events.push(Event::HurtItself(side, damage));
+            self.last_damage = damage;
+
+            // RBY bug: if the opponent has a substitute, the damage goes to it instead.
+            let (user, opponent) = split(&mut self.sides, side);
+            if opponent.has_substitute() {
+                opponent.damage(damage);
+                if !opponent.has_substitute() {
+                    events.push(Event::SubstituteBroke(1 - side));
+                }
+            } else {
+                user.damage_direct(damage);
+            }
+            return;
+        }
+
+        let current = match user.lock {
+            Some(Lock::Thrash(locked, _)) | Some(Lock::Rage(locked, _)) => locked,
+            Some(Lock::Charging(locked)) | Some(Lock::Trap(locked, _, _)) => locked,
+            None => used,
+        };
+        let blocked = user.bide.is_none() && user.is_disabled(current);
+        if blocked {
+            events.push(Event::MoveDisabled(side));
+        }
+
+        // The counter ticks down after the check, so a disable for N turns blocks N turns.
+        if user.tick_disable() {
+            events.push(Event::DisableEnded(side));
+        }
+        if blocked {
+            return;
+        }
+
+        // Full paralysis happens with a chance of 63/256, and interrupts multi-turn moves except
+        // for rage.
+        if user.condition() == Some(Condition::Paralyzed) && rand.chance(63) {
+            user.bide = None;
+            if !matches!(user.lock, Some(Lock::Rage(_, _))) {
+                user.lock = None;
+            }
+            events.push(Event::FullyParalyzed(side));
+            return;
+        }
+
+        if let Some((turns, total)) = user.bide {
+            // Bide adds the last damage dealt in the battle, even if it was dealt by the user.
+            let total = total.saturating_add(self.last_damage);
+            self.bide(side, turns - 1, total, events);
+            return;
+        }
+
+        match user.lock {
+            Some(Lock::Thrash(locked, turns)) => {
+                // The pokemon becomes confused at the start of its last turn of thrashing.
+                user.lock = Some(Lock::Thrash(locked, turns - 1));
+                if turns == 1 && user.confuse(rand) {
+                    events.push(Event::Confused(side));
+                }
+
+                self.use_move(rand, side, locked, events);
+
+                if turns == 1 {
+                    self.sides[side].lock = None;
+                }
+            }
+            Some(Lock::Rage(locked, _)) | Some(Lock::Charging(locked)) => {
+                self.use_move(rand, side, locked, events)
+            }
+            // Trapping moves keep dealing the same damage, without checking accuracy.
+            Some(Lock::Trap(locked, turns, damage)) => {
+                user.lock = Some(Lock::Trap(locked, turns - 1, damage)).filter(|_| turns > 1);
+                events.push(Event::UsedMove(side, locked.name()));
+                self.last_damage = damage;
+                self.deal_damage(side, damage, events);
+            }
+            None => {
+                let used = if user.must_struggle() {
+                    Move::fallback()
+                } else {
+                    used
+                };
+                user.use_pp(used);
+                self.use_move(rand, side, used, events)
+            }
+        }
+    }
+
+    /// Continue biding, unleashing the stored energy once no turns are left.
+    fn bide(&mut self, side: usize, turns: u8, total: u16, events: &mut Vec<Event>) {
+        let (user, target) = split(&mut self.sides, side);
+
+        if turns > 0 {
+            user.bide = Some((turns, total));
+            events.push(Event::StoringEnergy(side));
+            return;
+        }
+
+        user.bide = None;
+        events.push(Event::UnleashedEnergy(side));
+
+        // The unleashed damage ignores accuracy and type immunities.
+        let mut damage = total.saturating_mul(2);
+        if let Some(substitute) = target.substitute {
+            damage = damage.min(substitute);
+        }
+
+        if damage == 0 {
+            events.push(Event::Missed(side));
+            return;
+        }
+
+        self.last_damage = damage;
+        self.deal_damage(side, damage, events);
+    }
+
+    /// Deal attack damage from the given side to its opponent.
+    ///
+    /// Returns whether the attack broke a substitute.
+    fn deal_damage(&mut self, side: usize, damage: u16, events: &mut Vec<Event>) -> bool {
+        let target_side = 1 - side;
+        let target = &mut self.sides[target_side];
+
+        if let Some(Lock::Rage(_, _)) = target.lock {
+            if target.change_stat(Stat::Attack, 1, self.ruleset) {
+                events.push(Event::StatChanged(target_side, Stat::Attack, 1));
+            }
+        }
+
+        if target.has_substitute() {
+            target.damage(damage);
+            events.push(Event::SubstituteDamaged(target_side, damage));
+
+            if !target.has_substitute() {
+                events.push(Event::SubstituteBroke(target_side));
+                return true;
+            }
+        } else {
+            target.damage(damage);
+            events.push(Event::Damaged(target_side, damage));
+        }
+
+        false
+    }
+
+    /// Resolve a move used by the given side.
+    ///
+    /// This can be called recursively for moves that call other moves, like metronome.
+    fn use_move(
+        &mut self,
+        rand: &mut impl Chance,
+        side: usize,
+        used: &'static Move,
+        events: &mut Vec<Event>,
+    ) {
+        let target_side = 1 - side;
+        let last_move = self.last_move.replace(used);
+        let (user, target) = split(&mut self.sides, side);
+        user.last_used = Some(used);
+        events.push(Event::UsedMove(side, used.name()));
+
+        // Moves that only affect the user, and therefore can't miss.
+        match used.effect() {
+            MoveEffect::Recover => {
+                let before = user.hit_points();
+                if user.recover() {
+                    events.push(Event::Healed(side, user.hit_points() - before));
+                } else {
+                    events.push(Event::Failed(side));
+                }
+                return;
+            }
+            MoveEffect::Substitute => {
+                if user.create_substitute() {
+                    events.push(Event::Substitute(side));
+                } else {
+                    events.push(Event::Failed(side));
+                }
+                return;
+            }
+            MoveEffect::Bide => {
+                user.bide = Some((rand.range(2, 4), 0));
+                self.last_damage = 0;
+                events.push(Event::StoringEnergy(side));
+                return;
+            }
+            MoveEffect::Rest => {
+                let before = user.hit_points();
+                if user.rest() {
+                    events.push(Event::Healed(side, user.hit_points() - before));
+                    events.push(Event::Inflicted(side, Condition::Asleep(2)));
+                } else {
+                    events.push(Event::Failed(side));
+                }
+                return;
+            }
+            MoveEffect::StatUp(stat, change) => {
+                if user.change_stat(stat, change, self.ruleset) {
+                    events.push(Event::StatChanged(side, stat, change));
+                    if self.ruleset == Ruleset::Cartridge {
+                        target.apply_status_drop();
+                    }
+                } else {
+                    events.push(Event::Failed(side));
+                }
+                return;
+            }
+            MoveEffect::Reflect
+            | MoveEffect::LightScreen
+            | MoveEffect::Mist
+            | MoveEffect::FocusEnergy => {
+                let (succeeded, event) = match used.effect() {
+                    MoveEffect::Reflect => (user.use_reflect(), Event::Reflect(side)),
+                    MoveEffect::LightScreen => (user.use_light_screen(), Event::LightScreen(side)),
+                    MoveEffect::Mist => (user.use_mist(), Event::Mist(side)),
+                    _ => (user.use_focus_energy(), Event::FocusEnergy(side)),
+                };
+                events.push(if succeeded {
+                    event
+                } else {
+                    Event::Failed(side)
+                });
+                return;
+            }
+            MoveEffect::Haze => {
+                user.haze();
+                target.haze();
+                target.set_condition(None);
+                events.push(Event::Hazed);
+                return;
+            }
+            MoveEffect::Metronome => {
+                let called = metronome(rand);
+                self.use_move(rand, side, called, events);
+                return;
+            }
+            MoveEffect::MirrorMove => {
+                match target.last_used {
+                    Some(mirrored) if mirrored.effect() != MoveEffect::MirrorMove => {
+                        self.use_move(rand, side, mirrored, events);
+                    }
+                    _ => events.push(Event::Failed(side)),
+                }
+                return;
+            }
+            MoveEffect::NoEffect => {
+                events.push(Event::Failed(side));
+                return;
+            }
+            MoveEffect::Charge(_) => {
+                if let Some(Lock::Charging(_)) = user.lock {
+                    user.lock = None;
+                } else {
+                    user.lock = Some(Lock::Charging(used));
+                    events.push(Event::Charging(side));
+                    return;
+                }
+            }
+            _ => (),
+        }
+
+        if used.fails_against(target) {
+            events.push(Event::Failed(side));
+            return;
+        }
+
+        match used.effect() {
+            MoveEffect::SelfKO => {
+                user.damage_direct(user.hit_points());
+            }
+            MoveEffect::Thrash if user.lock.is_none() => {
+                user.lock = Some(Lock::Thrash(used, rand.range(2, 4)));
+            }
+            // One hit KO moves fail against faster pokemon.
+            MoveEffect::OneHitKO if user[Stat::Speed] < target[Stat::Speed] => {
+                events.push(Event::Failed(side));
+                return;
+            }
+            _ => (),
+        }
+
+        if target.is_invulnerable() {
+            events.push(Event::Missed(side));
+            return;
+        }
+
+        match used.effect() {
+            MoveEffect::Transform => {
+                user.transform(target);
+                events.push(Event::Transformed(side));
+                return;
+            }
+            MoveEffect::Conversion => {
+                user.convert(target);
+                events.push(Event::Converted(side));
+                return;
+            }
+            _ => (),
+        }
+
+        let accuracy = match user.lock {
+            // RBY bug: rage keeps using the modified accuracy of the previous turn as its base.
+            Some(Lock::Rage(_, accuracy)) => Some(accuracy),
+            _ => used.accuracy(),
+        };
+
+        if let Some(accuracy) = accuracy {
+            let accuracy = modify_accuracy(
+                accuracy,
+                user.get_modifier(Stat::Accuracy),
+                target.get_modifier(Stat::Evasion),
+            );
+
+            if used.effect() == MoveEffect::Rage {
+                user.lock = Some(Lock::Rage(used, accuracy));
+            }
+
+            if !rand.chance(accuracy) {
+                events.push(Event::Missed(side));
+
+                if used.effect() == MoveEffect::JumpKick {
+                    user.damage_direct(1);
+                    events.push(Event::Recoil(side, 1));
+                }
+                return;
+            }
+        }
+
+        let inflicted = match used.effect() {
+            MoveEffect::LeechSeed => {
+                if target.seed() {
+                    events.push(Event::Seeded(target_side));
+                } else {
+                    events.push(Event::Failed(side));
+                }
+                return;
+            }
+            MoveEffect::Confuse => {
+                if target.confuse(rand) {
+                    events.push(Event::Confused(target_side));
+                } else {
+                    events.push(Event::Failed(side));
+                }
+                return;
+            }
+            MoveEffect::Mimic => {
+                let known: Vec<_> = target.moves.iter().flatten().map(MoveSlot::known).collect();
+                match rand.pick(&known) {
+                    Some(&mimicked) if user.mimic(mimicked) => {
+                        events.push(Event::Mimicked(side, mimicked.name()));
+                    }
+                    _ => events.push(Event::Failed(side)),
+                }
+                return;
+            }
+            MoveEffect::Disable => {
+                match target.disable(rand) {
+                    Some(disabled) => events.push(Event::Disabled(target_side, disabled.name())),
+                    None => events.push(Event::Failed(side)),
+                }
+                return;
+            }
+            MoveEffect::StatDown(stat, change) => {
+                if !target.has_mist() && target.change_stat(stat, -change, self.ruleset) {
+                    events.push(Event::StatChanged(target_side, stat, -change));
+                    if self.ruleset == Ruleset::Cartridge {
+                        target.apply_status_drop();
+                    }
+                } else {
+                    events.push(Event::Failed(side));
+                }
+                return;
+            }
+            MoveEffect::Toxic => Some(Condition::Poisoned(true)),
+            MoveEffect::Poison => Some(Condition::Poisoned(false)),
+            MoveEffect::Sleep => Some(Condition::Asleep(rand.range(1, 8))),
+            // Paralysis moves respect type immunities, like thunder wave against ground types.
+            MoveEffect::Paralyze if !used.is_immune(target) => Some(Condition::Paralyzed),
+            MoveEffect::Paralyze => {
+                events.push(Event::Failed(side));
+                return;
+            }
+            _ => None,
+        };
+
+        if let Some(inflicted) = inflicted {
+            if target.inflict(inflicted) {
+                events.push(Event::Inflicted(target_side, inflicted));
+            } else {
+                events.push(Event::Failed(side));
+            }
+            return;
+        }
+
+        // Draining moves always miss substitutes, except for dream eater.
+        if used.effect() == MoveEffect::Drain && target.has_substitute() {
+            events.push(Event::Missed(side));
+            return;
+        }
+
+        let (critical, mut damage) = match used.effect() {
+            // Counter works off the last damage and move of the entire battle, not the target.
+            MoveEffect::Counter => match last_move {
+                Some(m) if m.can_be_countered() => (false, self.last_damage.saturating_mul(2)),
+                _ => (false, 0),
+            },
+            MoveEffect::OneHitKO if used.is_immune(target) => (false, 0),
+            MoveEffect::OneHitKO => (false, target.hit_points()),
+            _ => match used.fixed_damage(rand, user, target) {
+                Some(damage) => (false, damage),
+                None => {
+                    let critical = used.is_critical(rand, user, self.ruleset);
+                    (critical, used.damage(rand, user, target, critical))
+                }
+            },
+        };
+
+        if damage == 0 {
+            events.push(Event::Missed(side));
+            return;
+        }
+
+        if critical {
+            events.push(Event::CriticalHit(side));
+        }
+
+        if let Some(substitute) = target.substitute {
+            damage = damage.min(substitute);
+        }
+
+        self.last_damage = damage;
+
+        let hits = used.hit_count(rand);
+        let mut broke_substitute = false;
+        for hit in 1..=hits {
+            broke_substitute = self.deal_damage(side, damage, events);
+
+            if broke_substitute || !self.sides[target_side].is_alive() || hit == hits {
+                if hits > 1 {
+                    events.push(Event::Hits(target_side, hit));
+                }
+                break;
+            }
+        }
+
+        let (user, target) = split(&mut self.sides, side);
+
+        if let Some(recoil) = used.get_recoil(damage) {
+            user.damage_direct(recoil);
+            events.push(Event::Recoil(side, recoil));
+        }
+
+        if let Some(drain) = used.get_drain(damage) {
+            let before = user.hit_points();
+            user.heal(drain);
+            events.push(Event::Healed(side, user.hit_points() - before));
+        }
+
+        if !target.is_alive() {
+            return;
+        }
+
+        if used.effect() == MoveEffect::HyperBeam && !broke_substitute {
+            user.must_recharge = true;
+        }
+
+        if user.lock.is_none() {
+            match used.trap_turns(rand) {
+                Some(turns) if turns > 1 => user.lock = Some(Lock::Trap(used, turns - 1, damage)),
+                _ => (),
+            }
+        }
+
+        // Secondary effects still apply to a pokemon behind a substitute.
+        if used.confuses(rand) && target.confuse(rand) {
+            events.push(Event::Confused(target_side));
+        }
+
+        if used.flinches(rand) {
+            target.flinch();
+        }
+
+        // Fire moves that can burn thaw out a frozen target instead.
+        if let MoveEffect::BurnChance(_) = used.effect() {
+            if target.condition() == Some(Condition::Frozen) {
+                target.set_condition(None);
+                events.push(Event::Thawed(target_side));
+                return;
+            }
+        }
+
+        // Pokemon can't get a status condition from a move of their own type.
+        if let Some(condition) = used.secondary_condition(rand) {
+            if !target.has_type(used.move_type()) && target.inflict(condition) {
+                events.push(Event::Inflicted(target_side, condition));
+            }
+        }
+
+        if let Some(stat) = used.secondary_stat_drop(rand) {
+            if !target.has_mist() && target.change_stat(stat, -1, self.ruleset) {
+                events.push(Event::StatChanged(target_side, stat, -1));
+                if self.ruleset == Ruleset::Cartridge {
+                    target.apply_status_drop();
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Index<Stat> for BattleState<'a> {
+    type Output = u16;
+
+    fn index(&self, index: Stat) -> &Self::Output {
+        match index {
+            Stat::Accuracy | Stat::Evasion => unimplemented!(),
+            x => &self.stats[usize::from(x)],
+        }
+    }
+}
+
+impl<'a> Battle<'a> {
+    /// Get the members of a side's team, with the current state of the active pokemon.
+    fn members(&self, side: usize) -> impl Iterator<Item = &NonVolatileState<'a>> {
+        (0..self.team_size(side)).map(move |index| self.member(side, index))
+    }
+}
+
+// The stored entries of the active pokemon are out of date, so they are left out.
+impl<'a> PartialEq for Battle<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sides == other.sides
+            && self.active == other.active
+            && self.last_damage == other.last_damage
+            && self.last_move == other.last_move
+            && self.ruleset == other.ruleset
+            && (0..2).all(|side| self.members(side).eq(other.members(side)))
+    }
+}
+
+impl<'a> Eq for Battle<'a> {}
+
+impl<'a> Hash for Battle<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sides.hash(state);
+        self.active.hash(state);
+        self.last_damage.hash(state);
+        self.last_move.hash(state);
+        self.ruleset.hash(state);
+        for side in 0..2 {
+            self.members(side).for_each(|member| member.hash(state));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_heal() {
+        let pokemon = Pokemon::default();
+        let mut state = BattleState::new(&pokemon);
+        let max = state[Stat::HP];
+
+        assert_eq!(max - 100, state.damage(100));
+        assert_eq!(max - 50, state.heal(50));
+        // Healing should never exceed the maximum.
+        assert_eq!(max, state.heal(100));
+    }
+
+    #[test]
+    fn test_recovery_bug() {
+        let chansey = Pokemon {
+            base_stats: [250, 5, 5, 105, 50],
+            ..Default::default()
+        };
+        let mut state = BattleState::new(&chansey);
+        let max = state[Stat::HP];
+
+        // Recovery fails at full health
+        assert!(!state.recover());
+        assert!(!state.rest());
+
+        // Recovery fails if exactly 255 or 511 HP is missing.
+        for &missing in &[255, 511] {
+            state.heal(max);
+            state.damage(missing);
+            assert!(!state.recover());
+            assert!(!state.rest());
+            assert_eq!(max - missing, state.hit_points());
+        }
+
+        state.heal(max);
+        state.damage(256);
+        assert!(state.recover());
+        assert_eq!(max, state.hit_points());
+
+        state.set_condition(Some(Condition::Poisoned(false)));
+        state.damage(300);
+        assert!(state.rest());
+        assert_eq!(max, state.hit_points());
+        assert_eq!(Some(Condition::Asleep(2)), state.condition());
+    }
+
+    #[test]
+    fn test_toxic_leech_seed_counter() {
+        let pokemon = Pokemon::default();
+        let mut state = BattleState::new(&pokemon);
+        let mut opponent = BattleState::new(&pokemon);
+        let portion = state[Stat::HP] / 16;
+
+        assert!(state.inflict(Condition::Poisoned(true)));
+        assert!(state.seed());
+        opponent.damage(100);
+
+        // Toxic deals 1/16, leech seed then increments the shared counter to 2/16.
+        assert_eq!(3 * portion, state.apply_residual(&mut opponent));
+        assert_eq!(
+            opponent[Stat::HP] - 100 + 2 * portion,
+            opponent.hit_points()
+        );
+        // Next turn, toxic is at 3/16 and leech seed at 4/16.
+        assert_eq!(7 * portion, state.apply_residual(&mut opponent));
+
+        // Switching out turns toxic back into regular poison.
+        let state = BattleState::restore(state.switch_out());
+        assert_eq!(Some(Condition::Poisoned(false)), state.condition());
+        assert!(!state.is_seeded());
+    }
+
+    #[test]
+    fn test_confusion() {
+        let pokemon = Pokemon::default();
+        let mut state = BattleState::new(&pokemon);
+        let mut rng = StepRng::new(0, 1);
+
+        assert!(!state.check_confusion(&mut rng));
+        assert!(state.confuse(&mut rng));
+        assert!(!state.confuse(&mut rng));
+
+        // Confusion should wear off after at most five turns.
+        for _ in 0..5 {
+            state.check_confusion(&mut rng);
+        }
+        assert!(!state.is_confused());
+    }
+
+    #[test]
+    fn test_type_immunity() {
+        let pokemon = Pokemon {
+            types: [Some(Type::Grass), Some(Type::Poison)],
+            ..Default::default()
+        };
+        let mut state = BattleState::new(&pokemon);
+
+        assert!(!state.seed());
+        assert!(!state.inflict(Condition::Poisoned(true)));
+        assert!(state.inflict(Condition::Burned));
+        assert!(!state.inflict(Condition::Paralyzed));
+    }
+
+    #[test]
+    fn test_flinch() {
+        let mew = Pokemon {
+            base_stats: [100; 5],
+            types: [Some(Type::Psychic), None],
+            ..Default::default()
+        };
+        let snorlax = Pokemon {
+            base_stats: [160, 110, 65, 65, 30],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&mew), BattleState::new(&snorlax));
+        // Always hits, never a critical hit, always flinches
+        let mut rng = StepRng::new(60, 0);
+        let headbutt = Action::UseMove(&movedex::HEADBUTT);
+
+        // The slower pokemon can't make the faster one flinch.
+        let events = battle.turn(&mut rng, [headbutt, headbutt]);
+        assert!(!events.contains(&Event::Flinched(0)));
+        assert!(events.contains(&Event::Flinched(1)));
+
+        // Flinching cancels the hyper beam recharge.
+        let events = battle.turn(&mut rng, [Action::UseMove(&movedex::HYPER_BEAM), headbutt]);
+        assert!(events.contains(&Event::UsedMove(1, "Headbutt")));
+        assert!(!battle.side(0).must_recharge());
+
+        let events = battle.turn(&mut rng, [headbutt, headbutt]);
+        assert!(events.contains(&Event::UsedMove(0, "Headbutt")));
+    }
+
+    #[test]
+    fn test_substitute() {
+        let pokemon = Pokemon::default();
+        let mut state = BattleState::new(&pokemon);
+        let max = state[Stat::HP];
+
+        assert!(state.create_substitute());
+        assert!(!state.create_substitute());
+        assert_eq!(max - max / 4, state.hit_points());
+
+        // Damage is absorbed by the substitute, excess damage is lost.
+        assert_eq!(max - max / 4, state.damage(max / 4 - 1));
+        assert!(state.has_substitute());
+        assert_eq!(max - max / 4, state.damage(max));
+        assert!(!state.has_substitute());
+
+        // A pokemon with exactly a quarter of its HP faints.
+        state.damage(state.hit_points() - max / 4);
+        assert!(state.create_substitute());
+        assert!(!state.is_alive());
+    }
+
+    #[test]
+    fn test_substitute_battle() {
+        let mew = Pokemon {
+            base_stats: [100; 5],
+            types: [Some(Type::Psychic), None],
+            ..Default::default()
+        };
+        let rattata = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&mew), BattleState::new(&rattata));
+        let mut rng = StepRng::new(60, 0);
+        let substitute = Action::UseMove(&movedex::SUBSTITUTE);
+
+        // Drain moves miss a substitute.
+        let events = battle.turn(
+            &mut rng,
+            [Action::UseMove(&movedex::MEGA_DRAIN), substitute],
+        );
+        assert!(events.contains(&Event::Substitute(1)));
+        assert!(!events.contains(&Event::Missed(0)));
+        let events = battle.turn(
+            &mut rng,
+            [Action::UseMove(&movedex::MEGA_DRAIN), substitute],
+        );
+        assert!(events.contains(&Event::Missed(0)));
+
+        // Breaking a substitute with hyper beam doesn't require a recharge.
+        let events = battle.turn(
+            &mut rng,
+            [Action::UseMove(&movedex::HYPER_BEAM), substitute],
+        );
+        assert!(events.contains(&Event::SubstituteBroke(1)));
+        assert!(!battle.side(0).must_recharge());
+
+        // Self-destructing into a substitute still faints the user.
+        let events = battle.turn(&mut rng, [substitute, Action::UseMove(&movedex::EXPLOSION)]);
+        assert!(events.contains(&Event::SubstituteBroke(0)));
+        assert!(events.contains(&Event::Fainted(1)));
+        assert!(battle.side(0).is_alive());
+    }
+
+    #[test]
+    fn test_counter() {
+        let snorlax = Pokemon {
+            base_stats: [160, 110, 65, 65, 30],
+            ..Default::default()
+        };
+        let chansey = Pokemon {
+            base_stats: [250, 5, 5, 105, 50],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&chansey), BattleState::new(&snorlax));
+        let mut rng = StepRng::new(60, 0);
+        let counter = Action::UseMove(&movedex::COUNTER);
+
+        // Counter returns double the damage.
+        let events = battle.turn(&mut rng, [Action::UseMove(&movedex::HYPER_FANG), counter]);
+        let damage = battle.last_damage();
+        assert_eq!(Some(&Event::Damaged(1, damage / 2)), events.get(1));
+        assert_eq!(Some(&Event::Damaged(0, damage)), events.get(3));
+
+        // Special moves can't be countered.
+        let events = battle.turn(&mut rng, [Action::UseMove(&movedex::PSYBEAM), counter]);
+        assert!(events.contains(&Event::Missed(1)));
+    }
+
+    #[test]
+    fn test_bide() {
+        let snorlax = Pokemon {
+            base_stats: [160, 110, 65, 65, 30],
+            ..Default::default()
+        };
+        let chansey = Pokemon {
+            base_stats: [250, 5, 5, 105, 50],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&chansey), BattleState::new(&snorlax));
+        // Also makes bide last for two turns.
+        let mut rng = StepRng::new(60, 0);
+        let actions = [
+            Action::UseMove(&movedex::HYPER_FANG),
+            Action::UseMove(&movedex::BIDE),
+        ];
+
+        battle.turn(&mut rng, actions);
+        let damage = battle.side(1)[Stat::HP] - battle.side(1).hit_points();
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::StoringEnergy(1)));
+        assert!(battle.side(1).is_biding());
+
+        // Bide deals double the damage taken over two turns.
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::UnleashedEnergy(1)));
+        assert!(events.contains(&Event::Damaged(0, 4 * damage)));
+    }
+
+    #[test]
+    fn test_thrash_and_rage() {
+        let rattata = Pokemon::default();
+        let snorlax = Pokemon {
+            base_stats: [160, 110, 65, 65, 30],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&rattata), BattleState::new(&snorlax));
+        // Also makes thrashing last for 3 turns
+        let mut rng = StepRng::new(60, 0);
+        let substitute = Action::UseMove(&movedex::SUBSTITUTE);
+
+        battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::PETAL_DANCE),
+                Action::UseMove(&movedex::RAGE),
+            ],
+        );
+        assert!(battle.side(0).is_locked());
+        assert!(battle.side(1).is_locked());
+
+        // Both pokemon are locked into their moves; rage builds when hit.
+        let events = battle.turn(&mut rng, [substitute, substitute]);
+        assert!(events.contains(&Event::UsedMove(0, "Petal Dance")));
+        assert!(events.contains(&Event::StatChanged(1, Stat::Attack, 1)));
+        assert!(events.contains(&Event::UsedMove(1, "Rage")));
+
+        // Thrashing ends in confusion.
+        let events = battle.turn(&mut rng, [substitute, substitute]);
+        assert!(events.contains(&Event::Confused(0)));
+        assert!(events.contains(&Event::UsedMove(0, "Petal Dance")));
+        assert!(!battle.side(0).is_locked());
+        assert!(battle.side(1).is_locked());
+        assert_eq!(Modifier::from(2), battle.side(1).get_modifier(Stat::Attack));
+    }
+
+    #[test]
+    fn test_rage_accuracy_bug() {
+        let rattata = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&rattata), BattleState::new(&rattata));
+        let mut rng = StepRng::new(60, 0);
+        let rage = Action::UseMove(&movedex::RAGE);
+        let recover = Action::UseMove(&movedex::RECOVER);
+
+        battle.side_mut(1).modify_stat(Stat::Evasion, 6);
+        let events = battle.turn(&mut rng, [rage, recover]);
+        assert!(events.contains(&Event::Damaged(1, battle.last_damage())));
+        let events = battle.turn(&mut rng, [rage, recover]);
+        assert!(events.contains(&Event::Missed(0)));
+
+        // Even with evasion reset, rage keeps missing.
+        *battle.side_mut(1).get_modifier_mut(Stat::Evasion) = Modifier::default();
+        for _ in 0..5 {
+            let events = battle.turn(&mut rng, [rage, recover]);
+            assert!(events.contains(&Event::Missed(0)));
+        }
+    }
+
+    #[test]
+    fn test_metronome() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..1000 {
+            let called = metronome(&mut rng);
+            assert_ne!(MoveEffect::Metronome, called.effect());
+            assert_ne!("Struggle", called.name());
+        }
+    }
+
+    #[test]
+    fn test_mirror_move() {
+        let rattata = Pokemon::default();
+        let mew = Pokemon {
+            base_stats: [100; 5],
+            types: [Some(Type::Psychic), None],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&mew), BattleState::new(&rattata));
+        let mut rng = StepRng::new(60, 0);
+        let mirror_move = Action::UseMove(&movedex::MIRROR_MOVE);
+
+        // Nothing to mirror yet.
+        let events = battle.turn(&mut rng, [mirror_move, Action::UseMove(&movedex::GROWL)]);
+        assert!(events.contains(&Event::Failed(0)));
+        assert!(events.contains(&Event::StatChanged(0, Stat::Attack, -1)));
+
+        let events = battle.turn(&mut rng, [mirror_move, mirror_move]);
+        assert!(events.contains(&Event::UsedMove(0, "Growl")));
+        assert!(events.contains(&Event::StatChanged(1, Stat::Attack, -1)));
+        // Mirror move copies the move that was called, not mirror move itself.
+        assert!(events.contains(&Event::UsedMove(1, "Growl")));
+        assert_eq!(
+            Modifier::from(-2),
+            battle.side(0).get_modifier(Stat::Attack)
+        );
+    }
+
+    #[test]
+    fn test_transform() {
+        let mew = Pokemon {
+            base_stats: [100; 5],
+            types: [Some(Type::Psychic), None],
+            moves: [
+                Some(MoveSlot::new(&movedex::AMNESIA)),
+                Some(MoveSlot::new(&movedex::PSYCHIC)),
+                None,
+                None,
+            ],
+            ..Default::default()
+        };
+        let ditto = Pokemon {
+            level: 50,
+            base_stats: [48; 5],
+            moves: [Some(MoveSlot::new(&movedex::TRANSFORM)), None, None, None],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&mew), BattleState::new(&ditto));
+        let mut rng = StepRng::new(60, 0);
+
+        battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::AMNESIA),
+                Action::UseMove(&movedex::TRANSFORM),
+            ],
+        );
+
+        let (mew, ditto) = (battle.side(0), battle.side(1));
+        // Stats are copied including boosts, but HP and level are not.
+        assert_eq!(mew[Stat::Special], ditto[Stat::Special]);
+        assert_eq!(
+            mew.get_modifier(Stat::Special),
+            ditto.get_modifier(Stat::Special)
+        );
+        assert_ne!(mew[Stat::HP], ditto[Stat::HP]);
+        assert_eq!(50, ditto.pokemon().level);
+        assert!(ditto.has_type(Type::Psychic));
+        assert_eq!(Some("Psychic"), ditto.moves()[1].map(|s| s.known().name()));
+        assert_eq!(Some(5), ditto.moves()[1].map(|s| s.pp()));
+    }
+
+    #[test]
+    fn test_mimic() {
+        let mew = Pokemon {
+            base_stats: [100; 5],
+            types: [Some(Type::Psychic), None],
+            moves: [Some(MoveSlot::new(&movedex::AMNESIA)), None, None, None],
+            ..Default::default()
+        };
+        let rattata = Pokemon {
+            moves: [
+                Some(MoveSlot::new(&movedex::TACKLE)),
+                Some(MoveSlot::new(&movedex::MIMIC)),
+                None,
+                None,
+            ],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&mew), BattleState::new(&rattata));
+        let mut rng = StepRng::new(60, 0);
+
+        let events = battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::AMNESIA),
+                Action::UseMove(&movedex::MIMIC),
+            ],
+        );
+        assert!(events.contains(&Event::Mimicked(1, "Amnesia")));
+        // The mimicked move keeps the PP of mimic, which was already used once.
+        let mimicked = battle.side(1).moves()[1].unwrap();
+        assert_eq!("Amnesia", mimicked.known().name());
+        assert_eq!(movedex::MIMIC.pp() - 1, mimicked.pp());
+    }
+
+    #[test]
+    fn test_disable() {
+        let mew = Pokemon {
+            base_stats: [100; 5],
+            types: [Some(Type::Psychic), None],
+            moves: [Some(MoveSlot::new(&movedex::DISABLE)), None, None, None],
+            ..Default::default()
+        };
+        let rattata = Pokemon {
+            moves: [Some(MoveSlot::new(&movedex::GROWL)), None, None, None],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&mew), BattleState::new(&rattata));
+        let mut rng = StepRng::new(60, 0);
+        let actions = [
+            Action::UseMove(&movedex::DISABLE),
+            Action::UseMove(&movedex::GROWL),
+        ];
+
+        // A disable that lasts a single turn still blocks the move once.
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::Disabled(1, "Growl")));
+        assert!(events.contains(&Event::MoveDisabled(1)));
+        assert!(events.contains(&Event::DisableEnded(1)));
+
+        battle.side_mut(1).disabled = Some((&movedex::GROWL, 2));
+        let events = battle.turn(&mut rng, actions);
+        // A second disable fails while the first is active.
+        assert!(events.contains(&Event::Failed(0)));
+        assert!(events.contains(&Event::MoveDisabled(1)));
+        assert!(!events.contains(&Event::DisableEnded(1)));
+
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::MoveDisabled(1)));
+        assert!(events.contains(&Event::DisableEnded(1)));
+    }
+
+    #[test]
+    fn test_haze() {
+        let pokemon = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&pokemon), BattleState::new(&pokemon));
+        let mut rng = StepRng::new(60, 0);
+
+        let user = battle.side_mut(0);
+        user.inflict(Condition::Poisoned(true));
+        user.use_reflect();
+        user.modify_stat(Stat::Attack, 2);
+
+        let target = battle.side_mut(1);
+        target.inflict(Condition::Paralyzed);
+        target.use_mist();
+        target.use_focus_energy();
+        target.seed();
+        target.confuse(&mut rng);
+        target.disable(&mut rng);
+        target.modify_stat(Stat::Defense, -1);
+
+        let before = battle.side(0)[Stat::Attack];
+        let events = battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::HAZE),
+                Action::UseMove(&movedex::SPLASH),
+            ],
+        );
+        assert!(events.contains(&Event::Hazed));
+
+        let (user, target) = (battle.side(0), battle.side(1));
+        assert_eq!(Some(Condition::Poisoned(false)), user.condition());
+        assert!(!user.has_reflect());
+        assert_eq!(before / 2, user[Stat::Attack]);
+        assert_eq!(None, target.condition());
+        assert!(!target.has_mist() && !target.has_focus_energy() && !target.is_seeded());
+        assert!(!target.is_confused());
+        assert!(!target.is_disabled(&movedex::SPLASH));
+        assert_eq!(Modifier::default(), target.get_modifier(Stat::Defense));
+    }
+
+    #[test]
+    fn test_conversion() {
+        let porygon = Pokemon {
+            moves: [Some(MoveSlot::new(&movedex::CONVERSION)), None, None, None],
+            ..Default::default()
+        };
+        let mew = Pokemon {
+            base_stats: [100; 5],
+            types: [Some(Type::Psychic), None],
+            ..Default::default()
+        };
+        let mut battle = Battle::new(BattleState::new(&porygon), BattleState::new(&mew));
+        let mut rng = StepRng::new(60, 0);
+
+        battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::CONVERSION),
+                Action::UseMove(&movedex::SPLASH),
+            ],
+        );
+        assert!(battle.side(0).has_type(Type::Psychic));
+        assert!(!battle.side(0).has_type(Type::Normal));
+    }
+
+    #[test]
+    fn test_status_drop_glitch() {
+        let pokemon = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&pokemon), BattleState::new(&pokemon));
+        let mut rng = StepRng::new(60, 0);
+        let speed = battle.side(1)[Stat::Speed];
+
+        battle.side_mut(1).inflict(Condition::Paralyzed);
+        assert_eq!(speed / 4, battle.side(1)[Stat::Speed]);
+
+        // Any stat change by the opponent applies the drop again.
+        battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::SWORDS_DANCE),
+                Action::UseMove(&movedex::SPLASH),
+            ],
+        );
+        assert_eq!(speed / 16, battle.side(1)[Stat::Speed]);
+
+        // Changing its own speed recomputes the stat and loses the drop.
+        battle.side_mut(1).modify_stat(Stat::Speed, 1);
+        assert_eq!(speed * 3 / 2, battle.side(1)[Stat::Speed]);
+
+        let burned = BattleState::new(&pokemon);
+        let mut battle = Battle::with_ruleset(burned, BattleState::new(&pokemon), Ruleset::Stadium);
+        let attack = battle.side(0)[Stat::Attack];
+        battle.side_mut(0).inflict(Condition::Burned);
+        battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::SPLASH),
+                Action::UseMove(&movedex::GROWL),
+            ],
+        );
+        // Stadium doesn't reapply the drop to the opponent, and keeps it on the burned attack.
+        assert_eq!(
+            Modifier::from(-1).modify(attack) / 2,
+            battle.side(0)[Stat::Attack]
+        );
+    }
+
+    #[test]
+    fn test_sleep() {
+        let pokemon = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&pokemon), BattleState::new(&pokemon));
+        let mut rng = StepRng::new(60, 0);
+        battle.side_mut(0).damage(10);
+        let actions = [
+            Action::UseMove(&movedex::REST),
+            Action::UseMove(&movedex::SPLASH),
+        ];
+
+        battle.turn(&mut rng, actions);
+        assert_eq!(Some(Condition::Asleep(2)), battle.side(0).condition());
+
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::FastAsleep(0)));
+        // Waking up also takes a turn.
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::WokeUp(0)));
+        assert!(!events.contains(&Event::UsedMove(0, "Rest")));
+        assert_eq!(None, battle.side(0).condition());
+
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::UsedMove(0, "Rest")));
+    }
+
+    #[test]
+    fn test_freeze() {
+        let pokemon = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&pokemon), BattleState::new(&pokemon));
+        let mut rng = StepRng::new(60, 0);
+        battle.side_mut(0).modify_stat(Stat::Speed, 1);
+        battle.side_mut(1).inflict(Condition::Frozen);
+
+        let events = battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::SPLASH),
+                Action::UseMove(&movedex::TACKLE),
+            ],
+        );
+        assert!(events.contains(&Event::FrozenSolid(1)));
+
+        let events = battle.turn(
+            &mut rng,
+            [
+                Action::UseMove(&movedex::EMBER),
+                Action::UseMove(&movedex::TACKLE),
+            ],
+        );
+        assert!(events.contains(&Event::Thawed(1)));
+        assert!(events.contains(&Event::UsedMove(1, "Tackle")));
+    }
+
+    #[test]
+    fn test_full_paralysis() {
+        let pokemon = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&pokemon), BattleState::new(&pokemon));
+        battle.side_mut(0).inflict(Condition::Paralyzed);
+        let actions = [
+            Action::UseMove(&movedex::TACKLE),
+            Action::UseMove(&movedex::SPLASH),
+        ];
+
+        let events = battle.turn(&mut StepRng::new(62, 0), actions);
+        assert!(events.contains(&Event::FullyParalyzed(0)));
+
+        let events = battle.turn(&mut StepRng::new(63, 0), actions);
+        assert!(!events.contains(&Event::FullyParalyzed(0)));
+        assert!(events.contains(&Event::UsedMove(0, "Tackle")));
+    }
+
+    #[test]
+    fn test_pp() {
+        let mut pokemon = Pokemon::default();
+        pokemon.moves[0] = Some(MoveSlot::new(&movedex::TACKLE));
+        pokemon.moves[1] = Some(MoveSlot::new(&movedex::GROWL));
+        let mut battle = Battle::new(BattleState::new(&pokemon), BattleState::new(&pokemon));
+        let mut rng = StepRng::new(60, 0);
+        let actions = [
+            Action::UseMove(&movedex::GROWL),
+            Action::UseMove(&movedex::GROWL),
+        ];
+
+        battle.turn(&mut rng, actions);
+        assert_eq!(Some(39), battle.side(0).moves()[1].map(|s| s.pp()));
+
+        // With its only move with PP left disabled, a pokemon can pick moves without PP.
+        let user = battle.side_mut(0);
+        user.moves[1].as_mut().unwrap().set_pp(0);
+        user.disabled = Some((&movedex::TACKLE, 8));
+        let available: Vec<_> = user.available_moves().iter().map(|m| m.name()).collect();
+        assert_eq!(vec!["Growl"], available);
+        assert!(!user.must_struggle());
+
+        // Which makes the PP underflow.
+        battle.turn(&mut rng, actions);
+        let slot = battle.side(0).moves()[1].unwrap();
+        assert_eq!((63, 3), (slot.pp(), slot.pp_ups()));
+
+        // Once everything is out of PP, struggle is used instead.
+        let user = battle.side_mut(0);
+        user.moves[0].as_mut().unwrap().set_pp(0);
+        user.moves[1].as_mut().unwrap().set_pp(0);
+        assert!(user.must_struggle());
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::UsedMove(0, "Struggle")));
+
+        // PP used is kept after switching out.
+        let [user, _] = battle.sides;
+        let state = BattleState::restore(user.switch_out());
+        assert!(state.must_struggle());
+    }
+
+    #[test]
+    fn test_trap() {
+        let pokemon = Pokemon::default();
+        let mut battle = Battle::new(BattleState::new(&pokemon), BattleState::new(&pokemon));
+        battle.side_mut(0).modify_stat(Stat::Speed, 1);
+        // Hits and traps for 3 turns in total.
+        let mut rng = StepRng::new(60, 0);
+        let actions = [
+            Action::UseMove(&movedex::WRAP),
+            Action::UseMove(&movedex::TACKLE),
+        ];
+
+        let events = battle.turn(&mut rng, actions);
+        assert!(events.contains(&Event::Trapped(1)));
+        let damage = match events[1] {
+            Event::Damaged(1, damage) => damage,
+            _ => panic!("Unexpected event {:?}", events[1]),
+        };
+
+        assert!(battle.side(0).is_locked());
+        let events = battle.turn(&mut rng, actions);
+        assert_eq!(Event::Damaged(1, damage), events[1]);
+        assert!(events.contains(&Event::Trapped(1)));
+
+        // The trap is released before the slower target moves on the last turn.
+        let events = battle.turn(&mut rng, actions);
+        assert_eq!(Event::Damaged(1, damage), events[1]);
+        assert!(events.contains(&Event::UsedMove(1, "Tackle")));
+        assert!(!battle.side(0).is_locked());
+    }
+
+    #[test]
+    fn test_switch() {
+        let mut first = Pokemon::default();
+        first.moves[0] = Some(MoveSlot::new(&movedex::GROWL));
+        let team = [first, Pokemon::default()];
+        let opponent = [Pokemon::default()];
+        let mut battle = Battle::with_teams(&team, &opponent, Ruleset::Cartridge);
+        let mut rng = StepRng::new(60, 0);
+        let growl = Action::UseMove(&movedex::GROWL);
+
+        battle.turn(&mut rng, [growl, growl]);
+        assert_eq!(
+            Modifier::from(-1),
+            battle.side(0).get_modifier(Stat::Attack)
+        );
+
+        // Switching goes before any move.
+        let events = battle.turn(&mut rng, [Action::Switch(1), growl]);
+        assert_eq!(Event::SwitchedIn(0, 1), events[0]);
+        assert_eq!(1, battle.active(0));
+        assert_eq!(
+            Modifier::from(-1),
+            battle.side(0).get_modifier(Stat::Attack)
+        );
+
+        // Switching back resets the stat stages, but keeps the PP used.
+        battle.turn(
+            &mut rng,
+            [Action::Switch(0), Action::UseMove(&movedex::TACKLE)],
+        );
+        assert_eq!(Modifier::from(0), battle.side(0).get_modifier(Stat::Attack));
+        assert_eq!(Some(39), battle.side(0).moves()[0].map(|s| s.pp()));
+
+        // A fainted pokemon must be replaced before the next turn.
+        battle.side_mut(0).damage_direct(999);
+        assert!(battle.must_replace(0));
+        assert!(!battle.is_over());
+        let actions = battle.available_actions(0);
+        assert!(matches!(actions[..], [Action::Switch(1)]));
+        assert_eq!(Event::SwitchedIn(0, 1), battle.replace(0, 1));
+        assert_eq!(1, battle.remaining(0));
+
+        battle.side_mut(0).damage_direct(999);
+        assert!(battle.is_over());
+        assert_eq!(Some(1), battle.winner());
+    }
 }
@@ -0,0 +1,399 @@
+//! Damage calculator for a single attack between two pokemon.
+use std::fmt;
+
+use clap::{App, Arg, ArgMatches, ErrorKind};
+use serde::Serialize;
+
+use genwunsym::battle::{BattleState, Condition, Ruleset};
+use genwunsym::calc::{
+    damage_distribution, damage_range, hit_chance, ko_chances, KoScenario, Probability,
+};
+use genwunsym::movedex;
+use genwunsym::moves::{Move, MoveEffect};
+use genwunsym::pokedex::{self, Species};
+use genwunsym::pokemon::Pokemon;
+use genwunsym::stats::{hp_iv, Stat, StatSet, PERFECT_EVS, PERFECT_IVS};
+
+/// Report on a single calculation, printed as text or JSON.
+#[derive(Serialize)]
+struct Report {
+    attacker: &'static str,
+    defender: &'static str,
+    #[serde(rename = "move")]
+    used: &'static str,
+    defender_hp: u16,
+    /// Damage of a single hit.
+    damage: Option<DamageRange>,
+    critical: Option<DamageRange>,
+    /// Damage of all hits together, only for moves that hit more than once.
+    total: Option<DamageRange>,
+    hit_chance: f64,
+    critical_chance: f64,
+    ko_chances: Vec<f64>,
+}
+
+/// Range of damage, both absolute and as a percentage of the defender's maximum HP.
+#[derive(Serialize)]
+struct DamageRange {
+    min: u16,
+    max: u16,
+    min_percent: f64,
+    max_percent: f64,
+}
+
+impl DamageRange {
+    fn new((min, max): (u16, u16), max_hp: u16) -> Self {
+        let percent = |damage| f64::from(damage) * 100.0 / f64::from(max_hp);
+        DamageRange {
+            min,
+            max,
+            min_percent: percent(min),
+            max_percent: percent(max),
+        }
+    }
+}
+
+impl fmt::Display for DamageRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} - {} ({:.1}% - {:.1}%)",
+            self.min, self.max, self.min_percent, self.max_percent
+        )
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} {} vs. {}", self.attacker, self.used, self.defender)?;
+        let per_hit = if self.total.is_some() { " per hit" } else { "" };
+        match &self.damage {
+            Some(damage) => writeln!(f, "Damage{}: {}", per_hit, damage)?,
+            None => writeln!(f, "Damage: none")?,
+        }
+        if let Some(critical) = &self.critical {
+            writeln!(f, "Critical{}: {}", per_hit, critical)?;
+        }
+        if let Some(total) = &self.total {
+            writeln!(f, "Total damage: {}", total)?;
+        }
+        writeln!(f, "Hit chance: {:.1}%", self.hit_chance * 100.0)?;
+        writeln!(f, "Critical chance: {:.1}%", self.critical_chance * 100.0)?;
+
+        for (uses, chance) in self.ko_chances.iter().enumerate() {
+            let plural = if uses == 0 { "" } else { "s" };
+            writeln!(
+                f,
+                "KO in {} use{}: {:.1}%",
+                uses + 1,
+                plural,
+                chance * 100.0
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Stop with a usage error.
+fn fail(message: String) -> ! {
+    clap::Error::with_description(&message, ErrorKind::InvalidValue).exit()
+}
+
+fn to_f64(p: Probability) -> f64 {
+    *p.numer() as f64 / *p.denom() as f64
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, what: &str) -> T {
+    value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| fail(format!("Invalid {}: {}", what, value)))
+}
+
+/// Parse the DVs, either a single value for all stats or attack, defense, special and speed.
+///
+/// The HP DV is derived from the others, like in the games.
+fn parse_dvs(value: &str) -> StatSet {
+    let values: Vec<u16> = value.split(',').map(|v| parse_number(v, "DV")).collect();
+    let mut dvs = match values[..] {
+        [dv] => [dv; 5],
+        [attack, defense, special, speed] => [0, attack, defense, special, speed],
+        _ => fail(format!("Expected 1 or 4 DVs, got {}", value)),
+    };
+
+    if dvs.iter().any(|&dv| dv > 15) {
+        fail(format!("DVs must be between 0 and 15, got {}", value));
+    }
+
+    dvs[0] = hp_iv(&dvs);
+    dvs
+}
+
+/// Parse the stat experience, either a single value or HP, attack, defense, special and speed.
+fn parse_stat_exp(value: &str) -> StatSet {
+    let values: Vec<u16> = value
+        .split(',')
+        .map(|v| parse_number(v, "stat experience"))
+        .collect();
+    match values[..] {
+        [exp] => [exp; 5],
+        [hp, attack, defense, special, speed] => [hp, attack, defense, special, speed],
+        _ => fail(format!(
+            "Expected 1 or 5 stat experience values, got {}",
+            value
+        )),
+    }
+}
+
+/// Parse stat stages like `atk:+2,spc:-1`.
+fn parse_boosts(value: &str) -> Vec<(Stat, i8)> {
+    value
+        .split(',')
+        .map(|boost| {
+            let mut parts = boost.splitn(2, ':');
+            let stat = match parts.next().unwrap().trim().to_ascii_lowercase().as_str() {
+                "atk" => Stat::Attack,
+                "def" => Stat::Defense,
+                "spc" => Stat::Special,
+                "spe" => Stat::Speed,
+                "acc" => Stat::Accuracy,
+                "eva" => Stat::Evasion,
+                _ => fail(format!("Invalid stat stage: {}", boost)),
+            };
+            let stages = parts
+                .next()
+                .unwrap_or_else(|| fail(format!("Missing stage in {}", boost)));
+            let stages = parse_number(stages.trim_start_matches('+'), "stat stage");
+            (stat, stages)
+        })
+        .collect()
+}
+
+fn parse_status(value: &str) -> Condition {
+    match value.to_ascii_lowercase().as_str() {
+        "brn" => Condition::Burned,
+        "par" => Condition::Paralyzed,
+        "frz" => Condition::Frozen,
+        "slp" => Condition::Asleep(7),
+        "psn" => Condition::Poisoned(false),
+        "tox" => Condition::Poisoned(true),
+        _ => fail(format!("Invalid status: {}", value)),
+    }
+}
+
+/// Build a pokemon from the options for one side.
+fn pokemon(matches: &ArgMatches, side: &str) -> (&'static Species, Pokemon) {
+    let name = matches.value_of(side.to_ascii_uppercase()).unwrap();
+    let species = pokedex::find(name).unwrap_or_else(|| fail(format!("Unknown pokemon: {}", name)));
+    let level = matches
+        .value_of(format!("{}-level", side))
+        .map_or(100, |level| parse_number(level, "level"));
+    if level == 0 || level > 100 {
+        fail(format!("Level must be between 1 and 100, got {}", level));
+    }
+
+    let pokemon = Pokemon {
+        ivs: matches
+            .value_of(format!("{}-dvs", side))
+            .map_or(PERFECT_IVS, parse_dvs),
+        evs: matches
+            .value_of(format!("{}-statexp", side))
+            .map_or(PERFECT_EVS, parse_stat_exp),
+        ..species.pokemon(level)
+    };
+    (species, pokemon)
+}
+
+/// Apply the stat stages and status for one side.
+fn prepare(state: &mut BattleState, matches: &ArgMatches, side: &str) {
+    if let Some(boosts) = matches.value_of(format!("{}-boosts", side)) {
+        for (stat, stages) in parse_boosts(boosts) {
+            state.modify_stat(stat, stages);
+        }
+    }
+
+    if let Some(status) = matches.value_of(format!("{}-status", side)) {
+        state.inflict(parse_status(status));
+    }
+}
+
+const ATTACKER_ARGS: [&str; 5] = [
+    "attacker-level",
+    "attacker-dvs",
+    "attacker-statexp",
+    "attacker-boosts",
+    "attacker-status",
+];
+const DEFENDER_ARGS: [&str; 5] = [
+    "defender-level",
+    "defender-dvs",
+    "defender-statexp",
+    "defender-boosts",
+    "defender-status",
+];
+
+/// Build the options for one side, named by `names`.
+fn side_args(names: &'static [&'static str; 5]) -> Vec<Arg<'static, 'static>> {
+    let [level, dvs, statexp, boosts, status] = names;
+    vec![
+        Arg::with_name(level)
+            .long(level)
+            .takes_value(true)
+            .help("Level, 100 by default"),
+        Arg::with_name(dvs)
+            .long(dvs)
+            .takes_value(true)
+            .help("DVs, either one value or atk,def,spc,spe; 15 by default"),
+        Arg::with_name(statexp)
+            .long(statexp)
+            .takes_value(true)
+            .help("Stat experience, either one value or hp,atk,def,spc,spe; 65535 by default"),
+        Arg::with_name(boosts)
+            .long(boosts)
+            .takes_value(true)
+            .help("Stat stages, like atk:+2,spc:-1"),
+        Arg::with_name(status)
+            .long(status)
+            .takes_value(true)
+            .possible_values(&["brn", "par", "frz", "slp", "psn", "tox"])
+            .help("Status condition"),
+    ]
+}
+
+fn main() {
+    let matches = App::new("genwunsym-calc")
+        .about("Calculate the damage of a gen one attack")
+        .arg(
+            Arg::with_name("ATTACKER")
+                .required(true)
+                .help("Attacking species"),
+        )
+        .arg(
+            Arg::with_name("DEFENDER")
+                .required(true)
+                .help("Defending species"),
+        )
+        .arg(Arg::with_name("MOVE").required(true).help("Move used"))
+        .args(&side_args(&ATTACKER_ARGS))
+        .args(&side_args(&DEFENDER_ARGS))
+        .arg(
+            Arg::with_name("defender-hp")
+                .long("defender-hp")
+                .takes_value(true)
+                .help("Remaining HP of the defender, in percent"),
+        )
+        .arg(
+            Arg::with_name("reflect")
+                .long("reflect")
+                .help("The defender has reflect up"),
+        )
+        .arg(
+            Arg::with_name("light-screen")
+                .long("light-screen")
+                .help("The defender has light screen up"),
+        )
+        .arg(
+            Arg::with_name("stadium")
+                .long("stadium")
+                .help("Use the pokemon stadium mechanics"),
+        )
+        .arg(
+            Arg::with_name("uses")
+                .long("uses")
+                .takes_value(true)
+                .default_value("4")
+                .help("Number of uses to compute KO chances for"),
+        )
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .help("Print the result as JSON"),
+        )
+        .get_matches();
+
+    let name = matches.value_of("MOVE").unwrap();
+    let used: &'static Move =
+        movedex::find(name).unwrap_or_else(|| fail(format!("Unknown move: {}", name)));
+    let ruleset = if matches.is_present("stadium") {
+        Ruleset::Stadium
+    } else {
+        Ruleset::Cartridge
+    };
+    let uses = parse_number(matches.value_of("uses").unwrap(), "number of uses");
+
+    let (attacker_species, attacker_pokemon) = pokemon(&matches, "attacker");
+    let (defender_species, defender_pokemon) = pokemon(&matches, "defender");
+    let mut attacker = BattleState::new(&attacker_pokemon);
+    let mut defender = BattleState::new(&defender_pokemon);
+    prepare(&mut attacker, &matches, "attacker");
+    prepare(&mut defender, &matches, "defender");
+
+    if matches.is_present("reflect") {
+        defender.use_reflect();
+    }
+    if matches.is_present("light-screen") {
+        defender.use_light_screen();
+    }
+
+    let max_hp = defender[Stat::HP];
+    if let Some(percent) = matches.value_of("defender-hp") {
+        let percent: u32 = parse_number(percent.trim_end_matches('%'), "HP percentage");
+        if percent == 0 || percent > 100 {
+            fail(format!(
+                "HP percentage must be between 1 and 100, got {}",
+                percent
+            ));
+        }
+        let remaining = (u32::from(max_hp) * percent / 100).max(1) as u16;
+        defender.damage_direct(max_hp - remaining);
+    }
+
+    let distribution = damage_distribution(used, &attacker, &defender, ruleset);
+    let (damage, critical) = if used.power().is_some() {
+        (
+            Some(damage_range(used, &attacker, &defender, false)),
+            Some(damage_range(used, &attacker, &defender, true)),
+        )
+    } else {
+        (distribution.range(), None)
+    };
+    let total = match used.effect() {
+        MoveEffect::MultiHit | MoveEffect::DoubleHit | MoveEffect::Twineedle => {
+            distribution.range()
+        }
+        _ => None,
+    };
+
+    let critical_chance = if used.power().is_some() {
+        f64::from(used.critical_threshold(&attacker, ruleset)) / 256.0
+    } else {
+        0.0
+    };
+
+    let report = Report {
+        attacker: attacker_species.name,
+        defender: defender_species.name,
+        used: used.name(),
+        defender_hp: defender.hit_points(),
+        damage: damage.map(|range| DamageRange::new(range, max_hp)),
+        critical: critical.map(|range| DamageRange::new(range, max_hp)),
+        total: total.map(|range| DamageRange::new(range, max_hp)),
+        hit_chance: to_f64(hit_chance(used, &attacker, &defender)),
+        critical_chance,
+        ko_chances: ko_chances(
+            used,
+            &attacker,
+            &defender,
+            uses,
+            KoScenario::default(),
+            ruleset,
+        ),
+    };
+
+    if matches.is_present("json") {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print!("{}", report);
+    }
+}
@@ -0,0 +1,621 @@
+//! Battle simulator between two teams, for single battles or win rates over many battles.
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::ptr;
+
+use clap::{App, AppSettings, Arg, ArgMatches, ErrorKind, SubCommand};
+use rand::rngs::{OsRng, StdRng};
+use rand::{Rng, SeedableRng};
+
+use genwunsym::battle::{Action, Battle, Condition, Event, Ruleset};
+use genwunsym::mcts::MctsPlayer;
+use genwunsym::player::{GreedyPlayer, Player, RandomPlayer};
+use genwunsym::pokedex::{self, Species};
+use genwunsym::pokemon::Pokemon;
+use genwunsym::simulation::{matchup_matrix, play, win_rates, Estimate, Simulation};
+use genwunsym::stats::Stat;
+use genwunsym::team::{self, TeamMember};
+
+/// Stop with a usage error.
+fn fail(message: String) -> ! {
+    clap::Error::with_description(&message, ErrorKind::InvalidValue).exit()
+}
+
+fn load_team(path: &str) -> Vec<TeamMember> {
+    let text = fs::read_to_string(path)
+        .unwrap_or_else(|e| fail(format!("Failed to read {}: {}", path, e)));
+    team::parse(&text).unwrap_or_else(|e| fail(format!("Failed to load {}: {}", path, e)))
+}
+
+/// Turns events into readable messages.
+struct Log {
+    names: [Vec<String>; 2],
+    /// The active pokemon of both sides, as far as the log has seen.
+    active: [usize; 2],
+}
+
+impl Log {
+    fn name(&self, side: usize) -> String {
+        format!("p{} {}", side + 1, self.names[side][self.active[side]])
+    }
+
+    fn describe(&mut self, event: Event) -> String {
+        use Event::*;
+
+        match event {
+            UsedMove(side, used) => format!("{} used {}.", self.name(side), used),
+            Missed(side) => format!("{}'s attack missed!", self.name(side)),
+            Failed(_) => "But it failed!".to_owned(),
+            CriticalHit(_) => "Critical hit!".to_owned(),
+            Damaged(side, damage) => format!("{} took {} damage.", self.name(side), damage),
+            Recoil(side, damage) => {
+                format!("{} took {} damage in recoil.", self.name(side), damage)
+            }
+            Healed(side, amount) => format!("{} regained {} HP.", self.name(side), amount),
+            Residual(side, damage) => {
+                format!("{} took {} residual damage.", self.name(side), damage)
+            }
+            Inflicted(side, condition) => {
+                let description = match condition {
+                    Condition::Burned => "was burned",
+                    Condition::Paralyzed => "was paralyzed",
+                    Condition::Frozen => "was frozen solid",
+                    Condition::Asleep(_) => "fell asleep",
+                    Condition::Poisoned(false) => "was poisoned",
+                    Condition::Poisoned(true) => "was badly poisoned",
+                };
+                format!("{} {}!", self.name(side), description)
+            }
+            Seeded(side) => format!("{} was seeded!", self.name(side)),
+            Confused(side) => format!("{} became confused!", self.name(side)),
+            HurtItself(side, damage) => format!(
+                "{} hurt itself in its confusion for {} damage.",
+                self.name(side),
+                damage
+            ),
+            Flinched(side) => format!("{} flinched!", self.name(side)),
+            Substitute(side) => format!("{} made a substitute.", self.name(side)),
+            SubstituteDamaged(side, damage) => {
+                format!("{}'s substitute took {} damage.", self.name(side), damage)
+            }
+            SubstituteBroke(side) => format!("{}'s substitute broke!", self.name(side)),
+            StatChanged(side, stat, change) => {
+                let direction = if change > 0 { "rose" } else { "fell" };
+                let amount = if change.abs() > 1 { " sharply" } else { "" };
+                format!(
+                    "{}'s {} {}{}!",
+                    self.name(side),
+                    stat_name(stat),
+                    direction,
+                    amount
+                )
+            }
+            Charging(side) => format!("{} is charging up.", self.name(side)),
+            Transformed(side) => format!("{} transformed!", self.name(side)),
+            Mimicked(side, used) => format!("{} learned {}!", self.name(side), used),
+            Disabled(side, used) => format!("{}'s {} was disabled!", self.name(side), used),
+            MoveDisabled(side) => format!("{}'s move is disabled!", self.name(side)),
+            DisableEnded(side) => format!("{} is disabled no more!", self.name(side)),
+            Converted(side) => format!("{} converted its type!", self.name(side)),
+            Hazed => "All status changes were eliminated!".to_owned(),
+            Reflect(side) => format!("{} is protected against physical attacks!", self.name(side)),
+            LightScreen(side) => {
+                format!("{} is protected against special attacks!", self.name(side))
+            }
+            Mist(side) => format!("{} is shrouded in mist!", self.name(side)),
+            FocusEnergy(side) => format!("{} is getting pumped!", self.name(side)),
+            Hits(_, hits) => format!("Hit {} times!", hits),
+            StoringEnergy(side) => format!("{} is storing energy!", self.name(side)),
+            UnleashedEnergy(side) => format!("{} unleashed energy!", self.name(side)),
+            Recharging(side) => format!("{} must recharge!", self.name(side)),
+            FastAsleep(side) => format!("{} is fast asleep.", self.name(side)),
+            WokeUp(side) => format!("{} woke up!", self.name(side)),
+            FrozenSolid(side) => format!("{} is frozen solid!", self.name(side)),
+            Thawed(side) => format!("{} thawed out!", self.name(side)),
+            FullyParalyzed(side) => format!("{} is fully paralyzed!", self.name(side)),
+            Trapped(side) => format!("{} can't move!", self.name(side)),
+            Fainted(side) => format!("{} fainted!", self.name(side)),
+            SwitchedIn(side, index) => {
+                self.active[side] = index;
+                format!("p{} sent out {}!", side + 1, self.names[side][index])
+            }
+        }
+    }
+}
+
+fn stat_name(stat: Stat) -> &'static str {
+    match stat {
+        Stat::HP => "HP",
+        Stat::Attack => "attack",
+        Stat::Defense => "defense",
+        Stat::Special => "special",
+        Stat::Speed => "speed",
+        Stat::Accuracy => "accuracy",
+        Stat::Evasion => "evasiveness",
+    }
+}
+
+/// Player that asks for actions on the terminal.
+struct InteractivePlayer {
+    names: Vec<String>,
+}
+
+impl InteractivePlayer {
+    fn describe(&self, battle: &Battle, side: usize, action: Action) -> String {
+        match action {
+            Action::UseMove(used) => {
+                let slot = battle
+                    .side(side)
+                    .moves()
+                    .iter()
+                    .flatten()
+                    .find(|s| s.known().name() == used.name());
+                match slot {
+                    Some(slot) => format!("{} ({}/{} PP)", used.name(), slot.pp(), slot.max_pp()),
+                    None => used.name().to_owned(),
+                }
+            }
+            Action::Switch(index) => {
+                let member = battle.member(side, index);
+                let max = member.pokemon().get_stat(Stat::HP);
+                format!(
+                    "Switch to {} ({}/{} HP)",
+                    self.names[index],
+                    member.hit_points(),
+                    max
+                )
+            }
+        }
+    }
+}
+
+impl Player for InteractivePlayer {
+    fn choose(&mut self, battle: &Battle, side: usize) -> Action {
+        let actions = battle.available_actions(side);
+        if actions.len() == 1 {
+            return actions[0];
+        }
+
+        let opponent = battle.side(1 - side);
+        println!(
+            "The opponent has {:.0}% HP left.",
+            f64::from(opponent.hit_points()) * 100.0 / f64::from(opponent[Stat::HP])
+        );
+        let state = battle.side(side);
+        println!(
+            "What will {} do? ({}/{} HP)",
+            self.names[battle.active(side)],
+            state.hit_points(),
+            state[Stat::HP]
+        );
+        for (i, &action) in actions.iter().enumerate() {
+            println!("  {}) {}", i + 1, self.describe(battle, side, action));
+        }
+
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        loop {
+            print!("> ");
+            io::stdout().flush().unwrap();
+
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                _ => std::process::exit(0),
+            };
+            match line.trim().parse::<usize>() {
+                Ok(choice) if choice >= 1 && choice <= actions.len() => {
+                    return actions[choice - 1];
+                }
+                _ => println!("Enter a number between 1 and {}.", actions.len()),
+            }
+        }
+    }
+}
+
+fn create_player(kind: &str, iterations: u32, names: Vec<String>, seed: u64) -> Box<dyn Player> {
+    match kind {
+        "greedy" => Box::new(GreedyPlayer),
+        "random" => Box::new(RandomPlayer::new(StdRng::seed_from_u64(seed))),
+        "mcts" => Box::new(MctsPlayer::new(iterations, seed)),
+        "human" => Box::new(InteractivePlayer { names }),
+        _ => unreachable!(),
+    }
+}
+
+/// Arguments shared by a single battle and the win rate estimation.
+fn common_args(players: &'static [&'static str]) -> Vec<Arg<'static, 'static>> {
+    let player_arg = |name, help| {
+        Arg::with_name(name)
+            .long(name)
+            .takes_value(true)
+            .possible_values(players)
+            .default_value("greedy")
+            .help(help)
+    };
+
+    vec![
+        Arg::with_name("TEAM1")
+            .required(true)
+            .help("Team file of the first player, as a Showdown export or JSON"),
+        Arg::with_name("TEAM2")
+            .required(true)
+            .help("Team file of the second player, as a Showdown export or JSON"),
+        player_arg("p1", "Who chooses the actions of the first player"),
+        player_arg("p2", "Who chooses the actions of the second player"),
+    ]
+    .into_iter()
+    .chain(simulation_args())
+    .collect()
+}
+
+/// Arguments for the mechanics and randomness of battles.
+fn simulation_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("seed")
+            .long("seed")
+            .takes_value(true)
+            .help("Seed for the random number generator"),
+        Arg::with_name("stadium")
+            .long("stadium")
+            .help("Use the pokemon stadium mechanics"),
+        Arg::with_name("max-turns")
+            .long("max-turns")
+            .takes_value(true)
+            .default_value("1000")
+            .help("Stop a battle after this many turns"),
+        Arg::with_name("iterations")
+            .long("iterations")
+            .takes_value(true)
+            .default_value("1000")
+            .help("Number of iterations per choice for the mcts player"),
+    ]
+}
+
+/// Settings parsed from the common arguments.
+struct Settings {
+    teams: [Vec<TeamMember>; 2],
+    seed: u64,
+    max_turns: u32,
+    ruleset: Ruleset,
+}
+
+impl Settings {
+    fn new(matches: &ArgMatches) -> Self {
+        Settings {
+            teams: [
+                load_team(matches.value_of("TEAM1").unwrap()),
+                load_team(matches.value_of("TEAM2").unwrap()),
+            ],
+            seed: seed(matches),
+            max_turns: max_turns(matches),
+            ruleset: ruleset(matches),
+        }
+    }
+
+    fn names(&self) -> [Vec<String>; 2] {
+        [0, 1].map(|side| {
+            self.teams[side]
+                .iter()
+                .map(|m| m.name().to_owned())
+                .collect()
+        })
+    }
+
+    fn pokemon(&self) -> [Vec<Pokemon>; 2] {
+        [0, 1].map(|side| self.teams[side].iter().map(TeamMember::pokemon).collect())
+    }
+}
+
+fn seed(matches: &ArgMatches) -> u64 {
+    match matches.value_of("seed") {
+        Some(seed) => parse_number(seed, "seed"),
+        None => OsRng::new().unwrap().gen(),
+    }
+}
+
+fn max_turns(matches: &ArgMatches) -> u32 {
+    parse_number(matches.value_of("max-turns").unwrap(), "number of turns")
+}
+
+fn iterations(matches: &ArgMatches) -> u32 {
+    parse_number(
+        matches.value_of("iterations").unwrap(),
+        "number of iterations",
+    )
+}
+
+fn ruleset(matches: &ArgMatches) -> Ruleset {
+    if matches.is_present("stadium") {
+        Ruleset::Stadium
+    } else {
+        Ruleset::Cartridge
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, what: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(format!("Invalid {}: {}", what, value)))
+}
+
+/// Play a single battle and print the log.
+fn battle(matches: &ArgMatches) {
+    let settings = Settings::new(matches);
+    let names = settings.names();
+    let pokemon = settings.pokemon();
+    let seed = settings.seed;
+    let iterations = iterations(matches);
+    let mut players = [
+        create_player(
+            matches.value_of("p1").unwrap(),
+            iterations,
+            names[0].clone(),
+            seed ^ 1,
+        ),
+        create_player(
+            matches.value_of("p2").unwrap(),
+            iterations,
+            names[1].clone(),
+            seed ^ 2,
+        ),
+    ];
+
+    println!("Seed: {}", seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut battle = Battle::with_teams(&pokemon[0], &pokemon[1], settings.ruleset);
+    let mut log = Log {
+        names,
+        active: [0, 0],
+    };
+    for side in 0..2 {
+        println!("{}", log.describe(Event::SwitchedIn(side, 0)));
+    }
+
+    let [first, second] = &mut players;
+    let mut current = 0;
+    let turns = play(
+        &mut battle,
+        [first.as_mut(), second.as_mut()],
+        &mut rng,
+        settings.max_turns,
+        |turn, event| {
+            if turn != current {
+                current = turn;
+                println!("\nTurn {}", turn);
+            }
+            println!("{}", log.describe(event));
+        },
+    );
+
+    println!();
+    match battle.winner() {
+        Some(side) => println!("p{} wins!", side + 1),
+        None if battle.is_over() => println!("It's a draw!"),
+        None => println!("No winner after {} turns.", turns),
+    }
+}
+
+fn format_estimate(estimate: Estimate) -> String {
+    format!(
+        "{:.1}% ({:.1}% - {:.1}%)",
+        estimate.rate * 100.0,
+        estimate.lower * 100.0,
+        estimate.upper * 100.0
+    )
+}
+
+/// Play many battles and print the statistics.
+fn win_rate(matches: &ArgMatches) {
+    let settings = Settings::new(matches);
+    let names = settings.names();
+    let pokemon = settings.pokemon();
+    let kinds = [
+        matches.value_of("p1").unwrap(),
+        matches.value_of("p2").unwrap(),
+    ];
+    let iterations = iterations(matches);
+    let config = Simulation {
+        battles: parse_number(matches.value_of("battles").unwrap(), "number of battles"),
+        seed: settings.seed,
+        max_turns: settings.max_turns,
+        ruleset: settings.ruleset,
+    };
+
+    println!("Seed: {}", settings.seed);
+    let results = win_rates(
+        &pokemon[0],
+        &pokemon[1],
+        |side, seed| create_player(kinds[side], iterations, Vec::new(), seed),
+        &config,
+    );
+
+    println!("Battles: {}", results.battles);
+    println!("p1 wins: {}", format_estimate(results.win_rate(0)));
+    println!("p2 wins: {}", format_estimate(results.win_rate(1)));
+    println!("Draws: {}", format_estimate(results.draw_rate()));
+    if results.timeouts > 0 {
+        println!("Battles that hit the turn limit: {}", results.timeouts);
+    }
+    println!("Average turns: {:.1}", results.average_turns());
+
+    let battles = f64::from(results.battles.max(1));
+    for (side, names) in names.iter().enumerate() {
+        println!("\np{}:", side + 1);
+        for (i, name) in names.iter().enumerate() {
+            println!(
+                "  {}: {:.2} KOs per battle, fainted in {:.1}% of battles",
+                name,
+                f64::from(results.knockouts[side][i]) / battles,
+                f64::from(results.faints[side][i]) * 100.0 / battles
+            );
+        }
+    }
+}
+
+/// Quote a CSV field if it contains a comma, a quote or a line break.
+fn csv_field(text: &str) -> String {
+    if text.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Play one on one battles between a list of pokemon, and write the win rates as CSV.
+fn matchups(matches: &ArgMatches) {
+    let builtin = team::parse_sets(team::OU_SETS).unwrap();
+    let extra = match matches.value_of("sets") {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .unwrap_or_else(|e| fail(format!("Failed to read {}: {}", path, e)));
+            team::parse_sets(&text)
+                .unwrap_or_else(|e| fail(format!("Failed to load {}: {}", path, e)))
+        }
+        None => Vec::new(),
+    };
+    let has_set = |species: &Species| {
+        extra
+            .iter()
+            .chain(&builtin)
+            .any(|set| ptr::eq(set.species, species))
+    };
+    // Species without any set still take part, but without moves they can only struggle.
+    let defaults: Vec<_> = match matches.value_of("pokemon").unwrap() {
+        "all" => pokedex::SPECIES
+            .iter()
+            .filter(|species| !has_set(species))
+            .map(TeamMember::new)
+            .collect(),
+        _ => Vec::new(),
+    };
+    if !defaults.is_empty() {
+        let names: Vec<_> = defaults.iter().map(|m| m.name()).collect();
+        eprintln!(
+            "No sets for these pokemon, they can only struggle: {}",
+            names.join(", ")
+        );
+    }
+    // Sets from the file take precedence over the built-in ones.
+    let find = |species: &Species| {
+        extra
+            .iter()
+            .chain(&builtin)
+            .chain(&defaults)
+            .find(|set| ptr::eq(set.species, species))
+    };
+
+    let members: Vec<&TeamMember> = match matches.value_of("pokemon").unwrap() {
+        "ou" => builtin.iter().flat_map(|set| find(set.species)).collect(),
+        "all" => pokedex::SPECIES.iter().flat_map(find).collect(),
+        _ => extra.iter().collect(),
+    };
+
+    let pokemon: Vec<_> = members.iter().map(|m| m.pokemon()).collect();
+    let kind = matches.value_of("player").unwrap();
+    let iterations = iterations(matches);
+    let config = Simulation {
+        battles: parse_number(matches.value_of("battles").unwrap(), "number of battles"),
+        seed: seed(matches),
+        max_turns: max_turns(matches),
+        ruleset: ruleset(matches),
+    };
+    eprintln!("Seed: {}", config.seed);
+    let matrix = matchup_matrix(
+        &pokemon,
+        |_, seed| create_player(kind, iterations, Vec::new(), seed),
+        &config,
+    );
+
+    let mut output: Box<dyn Write> = match matches.value_of("output") {
+        Some(path) => Box::new(
+            fs::File::create(path)
+                .unwrap_or_else(|e| fail(format!("Failed to create {}: {}", path, e))),
+        ),
+        None => Box::new(io::stdout()),
+    };
+    let names: Vec<_> = members.iter().map(|m| csv_field(m.name())).collect();
+    let write = |output: &mut dyn Write| -> io::Result<()> {
+        writeln!(output, ",{}", names.join(","))?;
+        for (name, row) in names.iter().zip(&matrix) {
+            let rates: Vec<_> = row
+                .iter()
+                .map(|results| format!("{:.4}", results.win_rate(0).rate))
+                .collect();
+            writeln!(output, "{},{}", name, rates.join(","))?;
+        }
+        Ok(())
+    };
+    write(output.as_mut()).unwrap_or_else(|e| fail(format!("Failed to write output: {}", e)));
+}
+
+fn main() {
+    let matches = App::new("genwunsym-sim")
+        .about("Simulate a gen one battle between two teams")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .args(&common_args(&["greedy", "random", "mcts", "human"]))
+        .subcommand(
+            SubCommand::with_name("winrate")
+                .about("Estimate the win rates of two teams over many battles")
+                .args(&common_args(&["greedy", "random", "mcts"]))
+                .arg(
+                    Arg::with_name("battles")
+                        .long("battles")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("1000")
+                        .help("Number of battles to play"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("matchups")
+                .about("Write a matrix of one on one win rates as CSV")
+                .args(&simulation_args())
+                .arg(
+                    Arg::with_name("pokemon")
+                        .long("pokemon")
+                        .takes_value(true)
+                        .possible_values(&["ou", "all", "sets"])
+                        .default_value("ou")
+                        .help(
+                            "Pokemon to include: the OU tier, all 151, or those in the sets file",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("sets")
+                        .long("sets")
+                        .takes_value(true)
+                        .required_if("pokemon", "sets")
+                        .help("File with sets to use instead of the built-in OU sets"),
+                )
+                .arg(
+                    Arg::with_name("player")
+                        .long("player")
+                        .takes_value(true)
+                        .possible_values(&["greedy", "random", "mcts"])
+                        .default_value("greedy")
+                        .help("Who chooses the actions of both sides"),
+                )
+                .arg(
+                    Arg::with_name("battles")
+                        .long("battles")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("100")
+                        .help("Number of battles to play for every pair"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .short("o")
+                        .takes_value(true)
+                        .help("File to write the CSV to, instead of the standard output"),
+                ),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        ("winrate", Some(matches)) => win_rate(matches),
+        ("matchups", Some(matches)) => matchups(matches),
+        _ => battle(&matches),
+    }
+}
@@ -0,0 +1,618 @@
+//! Exact damage calculations, as opposed to sampling them in a battle.
+use std::collections::BTreeMap;
+
+use num::rational::Ratio;
+use num::Zero;
+
+use crate::battle::{recovery_fails, BattleState, Condition, Ruleset};
+use crate::moves::{modify_accuracy, Move, MoveEffect};
+use crate::stats::Stat;
+
+/// Exact probability of some outcome.
+pub type Probability = Ratio<u64>;
+
+/// Probability distribution of the damage dealt by a single use of a move.
+///
+/// Misses and moves that don't deal damage are included as dealing zero damage.
+#[derive(Debug, Clone, Default)]
+pub struct DamageDistribution {
+    outcomes: BTreeMap<u16, Probability>,
+}
+
+impl DamageDistribution {
+    /// Create a distribution that always deals the same damage.
+    pub fn certain(damage: u16) -> Self {
+        let mut distribution = DamageDistribution::default();
+        distribution.add(damage, Probability::from_integer(1));
+        distribution
+    }
+
+    fn add(&mut self, damage: u16, probability: Probability) {
+        if !probability.is_zero() {
+            *self
+                .outcomes
+                .entry(damage)
+                .or_insert_with(Probability::zero) += probability;
+        }
+    }
+
+    /// Iterate over all possible damage values and their probability, from low to high.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, Probability)> + '_ {
+        self.outcomes.iter().map(|(&damage, &p)| (damage, p))
+    }
+
+    /// Get the probability of dealing at least the given damage.
+    pub fn at_least(&self, damage: u16) -> Probability {
+        self.outcomes.range(damage..).map(|(_, &p)| p).sum()
+    }
+
+    /// Get the probability of dealing no damage at all.
+    pub fn miss_chance(&self) -> Probability {
+        self.outcomes
+            .get(&0)
+            .copied()
+            .unwrap_or_else(Probability::zero)
+    }
+
+    /// Get the lowest and highest damage possible when dealing any damage at all.
+    pub fn range(&self) -> Option<(u16, u16)> {
+        let min = self.outcomes.range(1..).next()?.0;
+        let max = self.outcomes.keys().next_back()?;
+        Some((*min, *max))
+    }
+
+    /// Get the expected damage.
+    pub fn expected(&self) -> Probability {
+        self.iter().map(|(damage, p)| p * u64::from(damage)).sum()
+    }
+
+    /// Scale every outcome by the given probability, moving the rest to zero damage.
+    fn with_chance(self, chance: Probability) -> Self {
+        let mut scaled = DamageDistribution::default();
+        scaled.add(0, Probability::from_integer(1) - chance);
+        for (damage, p) in self.iter() {
+            scaled.add(damage, p * chance);
+        }
+        scaled
+    }
+}
+
+/// Get the range of damage a hit can do, from the lowest to the highest random roll.
+pub fn damage_range(
+    used: &Move,
+    attacker: &BattleState,
+    defender: &BattleState,
+    critical: bool,
+) -> (u16, u16) {
+    (
+        used.damage_with_roll(attacker, defender, critical, 217),
+        used.damage_with_roll(attacker, defender, critical, 255),
+    )
+}
+
+/// Get the chance that a move hits, out of 256.
+///
+/// Even moves with perfect accuracy miss 1 in 256 times, only moves without an accuracy check
+/// always hit.
+pub fn hit_chance(used: &Move, attacker: &BattleState, defender: &BattleState) -> Probability {
+    if defender.is_invulnerable() {
+        return Probability::zero();
+    }
+
+    match used.accuracy() {
+        Some(accuracy) => {
+            let accuracy = modify_accuracy(
+                accuracy,
+                attacker.get_modifier(Stat::Accuracy),
+                defender.get_modifier(Stat::Evasion),
+            );
+            Probability::new(u64::from(accuracy), 256)
+        }
+        None => Probability::from_integer(1),
+    }
+}
+
+/// Compute the exact distribution of the damage a single use of a move deals.
+///
+/// This covers all 39 random rolls, critical hits, accuracy and the number of hits of multi-hit
+/// moves. Damage that depends on earlier turns, like counter and bide, is not covered and
+/// counts as zero. Substitutes are ignored.
+pub fn damage_distribution(
+    used: &Move,
+    attacker: &BattleState,
+    defender: &BattleState,
+    ruleset: Ruleset,
+) -> DamageDistribution {
+    if used.fails_against(defender) {
+        return DamageDistribution::certain(0);
+    }
+
+    let hit = match used.effect() {
+        MoveEffect::Counter | MoveEffect::Bide => return DamageDistribution::certain(0),
+        MoveEffect::OneHitKO
+            if used.is_immune(defender) || attacker[Stat::Speed] < defender[Stat::Speed] =>
+        {
+            return DamageDistribution::certain(0)
+        }
+        MoveEffect::OneHitKO => DamageDistribution::certain(defender.hit_points()),
+        MoveEffect::Psywave => {
+            // Uniform from 1 up to, but not including, one and a half times the level.
+            let level = u16::from(attacker.pokemon().level);
+            let max = (level * 3 / 2).max(2);
+            let mut distribution = DamageDistribution::default();
+            for damage in 1..max {
+                distribution.add(damage, Probability::new(1, u64::from(max - 1)));
+            }
+            distribution
+        }
+        _ => match used.deterministic_damage(attacker, defender) {
+            Some(damage) => DamageDistribution::certain(damage),
+            None => regular_damage(used, attacker, defender, ruleset),
+        },
+    };
+
+    hit_count_distribution(used, hit).with_chance(hit_chance(used, attacker, defender))
+}
+
+/// Distribution of regular damage over all random rolls, with and without a critical hit.
+fn regular_damage(
+    used: &Move,
+    attacker: &BattleState,
+    defender: &BattleState,
+    ruleset: Ruleset,
+) -> DamageDistribution {
+    let crit_chance = Probability::new(u64::from(used.critical_threshold(attacker, ruleset)), 256);
+    let mut distribution = DamageDistribution::default();
+
+    for &(critical, chance) in &[
+        (false, Probability::from_integer(1) - crit_chance),
+        (true, crit_chance),
+    ] {
+        for roll in 217..=255 {
+            let damage = used.damage_with_roll(attacker, defender, critical, roll);
+            distribution.add(damage, chance / 39);
+        }
+    }
+
+    distribution
+}
+
+/// Apply the number of hits of multi-hit moves. Every hit deals the same damage.
+fn hit_count_distribution(used: &Move, hit: DamageDistribution) -> DamageDistribution {
+    let counts: &[(u16, u64)] = match used.effect() {
+        MoveEffect::MultiHit => &[(2, 3), (3, 3), (4, 1), (5, 1)],
+        MoveEffect::DoubleHit | MoveEffect::Twineedle => &[(2, 8)],
+        _ => return hit,
+    };
+
+    let mut distribution = DamageDistribution::default();
+    for &(count, weight) in counts {
+        for (damage, p) in hit.iter() {
+            distribution.add(
+                damage.saturating_mul(count),
+                p * Probability::new(weight, 8),
+            );
+        }
+    }
+
+    distribution
+}
+
+/// What the defender does in between attacks, for `ko_chances`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct KoScenario {
+    /// Healing move the defender uses: recover, soft-boiled or rest. Other moves do nothing.
+    pub recovery: Option<&'static Move>,
+    /// The defender uses its healing move when its HP is at or below this value.
+    pub heal_threshold: u16,
+}
+
+/// Residual damage portions for a single turn: from burn or poison, and from leech seed.
+type Residual = (Option<u16>, Option<u16>);
+
+/// Compute the residual damage portions for a number of turns.
+///
+/// The portions only depend on the number of turns, since the toxic counter goes up by one for
+/// every portion.
+fn residual_portions(state: &BattleState, turns: usize) -> Vec<Residual> {
+    let mut state = state.clone();
+    (0..turns)
+        .map(|_| {
+            let condition = match state.condition() {
+                Some(Condition::Burned) | Some(Condition::Poisoned(_)) => {
+                    Some(state.residual_portion())
+                }
+                _ => None,
+            };
+            let seed = if state.is_seeded() {
+                Some(state.residual_portion())
+            } else {
+                None
+            };
+            (condition, seed)
+        })
+        .collect()
+}
+
+/// Apply residual damage to a pokemon, healing its opponent for leech seed.
+fn apply_residual(hit_points: &mut u16, opponent: &mut u16, opponent_max: u16, residual: Residual) {
+    if let Some(damage) = residual.0 {
+        *hit_points = hit_points.saturating_sub(damage);
+    }
+
+    if let Some(damage) = residual.1 {
+        let damage = damage.min(*hit_points);
+        *hit_points -= damage;
+        *opponent = (*opponent + damage).min(opponent_max);
+    }
+}
+
+/// Compute the chances that the attacker knocks out the defender within a number of uses.
+///
+/// The attacker uses the same move every turn. The result contains the chance of a KO within
+/// one use, within two uses, and so on. Besides the damage itself, this accounts for recoil,
+/// draining, residual damage from burn, poison and leech seed on both sides, and the defender
+/// healing according to the scenario. A defender that rests sleeps for two turns and is no longer
+/// hurt by its burn or poison. If the attacker faints first, there is no KO. Speed ties
+/// are resolved in favour of the attacker.
+pub fn ko_chances(
+    used: &Move,
+    attacker: &BattleState,
+    defender: &BattleState,
+    uses: usize,
+    scenario: KoScenario,
+    ruleset: Ruleset,
+) -> Vec<f64> {
+    let hit: Vec<_> = damage_distribution(used, attacker, defender, ruleset)
+        .iter()
+        .map(|(damage, p)| (damage, *p.numer() as f64 / *p.denom() as f64))
+        .collect();
+    let attacker_residual = residual_portions(attacker, uses);
+    let defender_residual = residual_portions(defender, uses);
+    let attacker_max = attacker[Stat::HP];
+    let defender_max = defender[Stat::HP];
+    let defender_first = defender[Stat::Speed] > attacker[Stat::Speed];
+
+    // Probability of each combination of defender and attacker HP, both alive, and the defender's
+    // recovery state.
+    let mut states = BTreeMap::new();
+    let rested = Rested::default();
+    states.insert((defender.hit_points(), attacker.hit_points(), rested), 1.0);
+    let mut knocked_out = 0.0;
+    let mut chances = Vec::with_capacity(uses);
+
+    for turn in 0..uses {
+        let mut next = BTreeMap::new();
+
+        for (&(defender_hp, attacker_hp, rested), &p) in &states {
+            let mut defender_hp = defender_hp;
+            let mut attacker_hp = attacker_hp;
+            let mut rested = rested;
+
+            if defender_first {
+                heal(scenario, &mut defender_hp, defender_max, &mut rested);
+                apply_residual(
+                    &mut defender_hp,
+                    &mut attacker_hp,
+                    attacker_max,
+                    rested.residual(defender_residual[turn]),
+                );
+                if defender_hp == 0 {
+                    knocked_out += p;
+                    continue;
+                }
+            }
+
+            for &(damage, q) in &hit {
+                let mut defender_hp = defender_hp;
+                let mut attacker_hp = attacker_hp;
+
+                if damage > 0 {
+                    let damage = match used.effect() {
+                        MoveEffect::SuperFang => (defender_hp / 2).max(1),
+                        MoveEffect::OneHitKO => defender_hp,
+                        _ => damage,
+                    };
+                    defender_hp = defender_hp.saturating_sub(damage);
+                    if let Some(recoil) = used.get_recoil(damage) {
+                        attacker_hp = attacker_hp.saturating_sub(recoil);
+                    }
+                    if let Some(drain) = used.get_drain(damage) {
+                        attacker_hp = (attacker_hp + drain).min(attacker_max);
+                    }
+                }
+                if used.effect() == MoveEffect::SelfKO {
+                    attacker_hp = 0;
+                }
+
+                if defender_hp == 0 {
+                    knocked_out += p * q;
+                    continue;
+                }
+
+                if attacker_hp > 0 {
+                    apply_residual(
+                        &mut attacker_hp,
+                        &mut defender_hp,
+                        defender_max,
+                        attacker_residual[turn],
+                    );
+                }
+
+                if !defender_first {
+                    heal(scenario, &mut defender_hp, defender_max, &mut rested);
+                    apply_residual(
+                        &mut defender_hp,
+                        &mut attacker_hp,
+                        attacker_max,
+                        rested.residual(defender_residual[turn]),
+                    );
+                    if defender_hp == 0 {
+                        knocked_out += p * q;
+                        continue;
+                    }
+                }
+
+                if attacker_hp > 0 {
+                    *next
+                        .entry((defender_hp, attacker_hp, rested))
+                        .or_insert(0.0) += p * q;
+                }
+            }
+        }
+
+        chances.push(knocked_out);
+        states = next;
+    }
+
+    chances
+}
+
+/// What resting did to the defender, for `ko_chances`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+struct Rested {
+    /// Turns the defender still sleeps, including the turn it wakes up.
+    sleep: u8,
+    /// Whether rest cured the defender's burn or poison.
+    cured: bool,
+}
+
+impl Rested {
+    /// Drop the damage from burn or poison once rest has cured it.
+    fn residual(self, residual: Residual) -> Residual {
+        if self.cured {
+            (None, residual.1)
+        } else {
+            residual
+        }
+    }
+}
+
+/// Let the defender heal, if the scenario says it should and it is awake.
+fn heal(scenario: KoScenario, hit_points: &mut u16, max: u16, rested: &mut Rested) {
+    if rested.sleep > 0 {
+        rested.sleep -= 1;
+        return;
+    }
+
+    match scenario.recovery {
+        Some(recovery)
+            if *hit_points <= scenario.heal_threshold && !recovery_fails(max, *hit_points) =>
+        {
+            match recovery.effect() {
+                MoveEffect::Recover => *hit_points = (*hit_points + max / 2).min(max),
+                MoveEffect::Rest => {
+                    *hit_points = max;
+                    *rested = Rested {
+                        sleep: 2,
+                        cured: true,
+                    };
+                }
+                _ => (),
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::movedex;
+    use crate::pokemon::Pokemon;
+
+    use super::*;
+
+    #[test]
+    fn test_damage_distribution() {
+        let pokemon = Pokemon::default();
+        let attacker = BattleState::new(&pokemon);
+        let defender = BattleState::new(&pokemon);
+        let tackle = &movedex::TACKLE;
+
+        let distribution = damage_distribution(tackle, &attacker, &defender, Ruleset::Cartridge);
+        let total: Probability = distribution.iter().map(|(_, p)| p).sum();
+        assert_eq!(Probability::from_integer(1), total);
+
+        // Tackle has 95% accuracy, or 242/256.
+        assert_eq!(Probability::new(14, 256), distribution.miss_chance());
+
+        let (low, _) = damage_range(tackle, &attacker, &defender, false);
+        let (_, high) = damage_range(tackle, &attacker, &defender, true);
+        assert_eq!(Some((low, high)), distribution.range());
+        assert_eq!(Probability::new(242, 256), distribution.at_least(low));
+    }
+
+    #[test]
+    fn test_fixed_damage_distribution() {
+        let pokemon = Pokemon::default();
+        let attacker = BattleState::new(&pokemon);
+        let defender = BattleState::new(&pokemon);
+
+        // Sonic boom deals 20 damage whenever it hits.
+        let distribution = damage_distribution(
+            &movedex::SONIC_BOOM,
+            &attacker,
+            &defender,
+            Ruleset::Cartridge,
+        );
+        assert_eq!(Probability::new(229, 256), distribution.at_least(20));
+        assert_eq!(Some((20, 20)), distribution.range());
+    }
+
+    #[test]
+    fn test_multi_hit_distribution() {
+        let hit = DamageDistribution::certain(10);
+        let outcomes =
+            |used| -> Vec<_> { hit_count_distribution(used, hit.clone()).iter().collect() };
+
+        // Fury attack hits two or three times with 3/8 each, and four or five times with 1/8.
+        let expected = vec![
+            (20, Probability::new(3, 8)),
+            (30, Probability::new(3, 8)),
+            (40, Probability::new(1, 8)),
+            (50, Probability::new(1, 8)),
+        ];
+        assert_eq!(expected, outcomes(&movedex::FURY_ATTACK));
+        assert_eq!(
+            vec![(20, Probability::from_integer(1))],
+            outcomes(&movedex::DOUBLE_KICK)
+        );
+        assert_eq!(
+            vec![(10, Probability::from_integer(1))],
+            outcomes(&movedex::TACKLE)
+        );
+
+        let pokemon = Pokemon::default();
+        let attacker = BattleState::new(&pokemon);
+        let defender = BattleState::new(&pokemon);
+        let distribution = damage_distribution(
+            &movedex::DOUBLE_KICK,
+            &attacker,
+            &defender,
+            Ruleset::Cartridge,
+        );
+        // Double kick always hits twice for an even amount of damage.
+        assert!(distribution.iter().all(|(damage, _)| damage % 2 == 0));
+    }
+
+    #[test]
+    fn test_ko_chances() {
+        let pokemon = Pokemon::default();
+        let attacker = BattleState::new(&pokemon);
+        let mut defender = BattleState::new(&pokemon);
+        let scenario = KoScenario::default();
+
+        // Sonic boom always deals 20 damage, so three hits are needed.
+        defender.damage(defender.hit_points() - 60);
+        let chances = ko_chances(
+            &movedex::SONIC_BOOM,
+            &attacker,
+            &defender,
+            4,
+            scenario,
+            Ruleset::Cartridge,
+        );
+        let hit: f64 = 229.0 / 256.0;
+        assert_eq!(0.0, chances[1]);
+        assert!((hit.powi(3) - chances[2]).abs() < 1e-9);
+        let expected = hit.powi(4) + 4.0 * hit.powi(3) * (1.0 - hit);
+        assert!((expected - chances[3]).abs() < 1e-9);
+
+        // Poison takes off another 16 HP every turn, so two hits are enough now.
+        defender.inflict(Condition::Poisoned(false));
+        let chances = ko_chances(
+            &movedex::SONIC_BOOM,
+            &attacker,
+            &defender,
+            2,
+            scenario,
+            Ruleset::Cartridge,
+        );
+        assert!((hit * hit - chances[1]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ko_chances_recovery() {
+        let pokemon = Pokemon::default();
+        let attacker = BattleState::new(&pokemon);
+        let defender = BattleState::new(&pokemon);
+        let scenario = KoScenario {
+            recovery: Some(&movedex::RECOVER),
+            heal_threshold: defender[Stat::HP],
+        };
+
+        // Recovering every turn outheals sonic boom.
+        let chances = ko_chances(
+            &movedex::SONIC_BOOM,
+            &attacker,
+            &defender,
+            20,
+            scenario,
+            Ruleset::Cartridge,
+        );
+        assert_eq!(0.0, chances[19]);
+
+        // Soft-boiled heals just like recover.
+        let scenario = KoScenario {
+            recovery: Some(&movedex::SOFT_BOILED),
+            ..scenario
+        };
+        let chances = ko_chances(
+            &movedex::SONIC_BOOM,
+            &attacker,
+            &defender,
+            20,
+            scenario,
+            Ruleset::Cartridge,
+        );
+        assert_eq!(0.0, chances[19]);
+    }
+
+    #[test]
+    fn test_ko_chances_rest() {
+        let pokemon = Pokemon {
+            level: 20,
+            ..Default::default()
+        };
+        let attacker = BattleState::new(&pokemon);
+        let mut defender = BattleState::new(&pokemon);
+        let max = defender[Stat::HP];
+        assert!(max > 40 && max <= 80);
+        let dragon_rage = &movedex::DRAGON_RAGE;
+        let cartridge = Ruleset::Cartridge;
+
+        let chances = ko_chances(
+            dragon_rage,
+            &attacker,
+            &defender,
+            3,
+            Default::default(),
+            cartridge,
+        );
+        assert!(chances[1] > 0.99);
+
+        // Rest heals fully after the first hit, and the defender sleeps through the next two.
+        let scenario = KoScenario {
+            recovery: Some(&movedex::REST),
+            heal_threshold: max - 1,
+        };
+        let chances = ko_chances(dragon_rage, &attacker, &defender, 3, scenario, cartridge);
+        assert_eq!(0.0, chances[1]);
+        assert!(chances[2] > 0.98);
+
+        // Rest cures the burn.
+        defender.set_condition(Some(Condition::Burned));
+        let growl = &movedex::GROWL;
+        let chances = ko_chances(
+            growl,
+            &attacker,
+            &defender,
+            20,
+            Default::default(),
+            cartridge,
+        );
+        assert_eq!(1.0, chances[19]);
+        let chances = ko_chances(growl, &attacker, &defender, 20, scenario, cartridge);
+        assert_eq!(0.0, chances[19]);
+    }
+}
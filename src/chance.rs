@@ -0,0 +1,51 @@
+//! The random decisions made during a battle.
+use num::PrimInt;
+use rand::distributions::uniform::SampleUniform;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Source of the random decisions in a battle.
+///
+/// This is implemented for every random number generator, drawing the same numbers as the game
+/// logic did before. Other implementations can walk through every possible outcome instead, like
+/// the solver does.
+pub trait Chance {
+    /// Returns true with a probability of `numerator` out of 256.
+    ///
+    /// This is how the game checks most probabilities, by comparing a random byte.
+    fn chance(&mut self, numerator: u8) -> bool;
+
+    /// Flip a coin.
+    fn coin(&mut self) -> bool;
+
+    /// Get a random byte.
+    fn byte(&mut self) -> u8;
+
+    /// Get a random number in `low..high`.
+    fn range<T: SampleUniform + PrimInt>(&mut self, low: T, high: T) -> T;
+
+    /// Pick a random item, or None if there are no items.
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T>;
+}
+
+impl<R: Rng + ?Sized> Chance for R {
+    fn chance(&mut self, numerator: u8) -> bool {
+        self.gen::<u8>() < numerator
+    }
+
+    fn coin(&mut self) -> bool {
+        self.gen()
+    }
+
+    fn byte(&mut self) -> u8 {
+        self.gen()
+    }
+
+    fn range<T: SampleUniform + PrimInt>(&mut self, low: T, high: T) -> T {
+        self.gen_range(low, high)
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.choose(self)
+    }
+}
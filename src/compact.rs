@@ -0,0 +1,596 @@
+//! Compact, owned copies of battle states, for search.
+//!
+//! The states in `battle` borrow the pokemon they belong to. The compact states here refer to
+//! pokemon by their index in a roster instead, and to moves by their index in the movedex, so
+//! they can be copied, hashed and stored freely. A roster is a slice of all pokemon that can
+//! appear in a state; for a battle, it's the first team followed by the second.
+//!
+//! Everything that follows from the roster is left out, and the rest is packed into as few bits
+//! as it needs, so a battle between two full teams fits in two cache lines. These types only
+//! convert to and from the full ones; the solver and the MCTS player work on `Battle` directly.
+use std::ptr;
+
+use crate::battle::{Battle, BattleState, Condition, Lock, NonVolatileState, PlainState, Ruleset};
+use crate::movedex;
+use crate::moves::{Move, MoveSlot};
+use crate::pokemon::Pokemon;
+use crate::stats::{Modifier, Stat};
+use crate::team::MAX_TEAM_SIZE;
+use crate::types::Type;
+
+/// All types, in the order of their codes.
+const TYPES: [Type; 15] = [
+    Type::Normal,
+    Type::Fighting,
+    Type::Flying,
+    Type::Poison,
+    Type::Ground,
+    Type::Rock,
+    Type::Bug,
+    Type::Ghost,
+    Type::Fire,
+    Type::Water,
+    Type::Grass,
+    Type::Electric,
+    Type::Psychic,
+    Type::Ice,
+    Type::Dragon,
+];
+
+/// Bits for an index in the roster, which holds up to two full teams.
+const POKEMON_BITS: u32 = 4;
+/// Bits for hit points and stats. Stats are capped at 999, and HP stays below 1024 up to level
+/// 100.
+const STAT_BITS: u32 = 10;
+/// Bits for a non-volatile state, without the index of its pokemon.
+const NV_BITS: usize = 38;
+/// Bits for a whole `CompactState`.
+const STATE_BITS: usize = 290;
+
+/// Writes values into a fixed number of bits, one after the other.
+struct Writer<'b> {
+    words: &'b mut [u64],
+    position: usize,
+}
+
+impl<'b> Writer<'b> {
+    fn new(words: &'b mut [u64]) -> Self {
+        Writer { words, position: 0 }
+    }
+
+    /// Write a value in the given number of bits. Returns None if it doesn't fit.
+    fn put(&mut self, value: impl Into<u64>, width: u32) -> Option<()> {
+        let value = value.into();
+        if width < 64 && value >> width != 0 {
+            return None;
+        }
+
+        let (word, offset) = (self.position / 64, self.position % 64);
+        self.words[word] |= value << offset;
+        if offset + width as usize > 64 {
+            self.words[word + 1] |= value >> (64 - offset);
+        }
+        self.position += width as usize;
+        Some(())
+    }
+
+    fn flag(&mut self, value: bool) {
+        self.put(value, 1).unwrap();
+    }
+
+    /// Write a move from the movedex, or no move at all. Returns None if it isn't in the movedex.
+    fn known(&mut self, known: Option<&Move>) -> Option<()> {
+        match known {
+            Some(known) => self.put(movedex::index(known)? as u64 + 1, 8),
+            None => self.put(0u8, 8),
+        }
+    }
+
+    /// Leave bits at zero.
+    fn skip(&mut self, width: usize) {
+        self.position += width;
+    }
+}
+
+/// Reads the values written by a `Writer`, in the same order.
+struct Reader<'b> {
+    words: &'b [u64],
+    position: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn new(words: &'b [u64], position: usize) -> Self {
+        Reader { words, position }
+    }
+
+    fn get(&mut self, width: u32) -> u64 {
+        let (word, offset) = (self.position / 64, self.position % 64);
+        let mut value = self.words[word] >> offset;
+        if offset + width as usize > 64 {
+            value |= self.words[word + 1] << (64 - offset);
+        }
+        self.position += width as usize;
+
+        if width < 64 {
+            value & ((1 << width) - 1)
+        } else {
+            value
+        }
+    }
+
+    fn flag(&mut self) -> bool {
+        self.get(1) == 1
+    }
+
+    fn known(&mut self) -> Option<&'static Move> {
+        match self.get(8) as usize {
+            0 => None,
+            index => Some(movedex::MOVES[index - 1]),
+        }
+    }
+
+    fn skip(&mut self, width: usize) {
+        self.position += width;
+    }
+}
+
+/// Copy a number of bits from a reader to a writer.
+fn copy(reader: &mut Reader, writer: &mut Writer, mut width: usize) {
+    while width > 0 {
+        let chunk = width.min(64) as u32;
+        writer.put(reader.get(chunk), chunk).unwrap();
+        width -= chunk as usize;
+    }
+}
+
+/// Find a pokemon in a roster, by identity.
+fn roster_index(roster: &[&Pokemon], pokemon: &Pokemon) -> Option<u8> {
+    roster
+        .iter()
+        .position(|&p| ptr::eq(p, pokemon))
+        .map(|index| index as u8)
+}
+
+fn put_condition(writer: &mut Writer, condition: Option<Condition>) -> Option<()> {
+    let code = match condition {
+        None => 0,
+        Some(Condition::Burned) => 1,
+        Some(Condition::Paralyzed) => 2,
+        Some(Condition::Frozen) => 3,
+        Some(Condition::Poisoned(bad)) => 4 + u8::from(bad),
+        Some(Condition::Asleep(turns)) => 8u8.checked_add(turns)?,
+    };
+    writer.put(code, 4)
+}
+
+fn get_condition(reader: &mut Reader) -> Option<Condition> {
+    match reader.get(4) as u8 {
+        0 => None,
+        1 => Some(Condition::Burned),
+        2 => Some(Condition::Paralyzed),
+        3 => Some(Condition::Frozen),
+        code if code < 8 => Some(Condition::Poisoned(code == 5)),
+        code => Some(Condition::Asleep(code - 8)),
+    }
+}
+
+/// Write a non-volatile state, without its pokemon.
+///
+/// Only the PP of the moves is written, since the moves themselves are those of the pokemon.
+/// Returns None if they aren't.
+fn put_nv(writer: &mut Writer, state: &NonVolatileState) -> Option<()> {
+    writer.put(state.hit_points(), STAT_BITS)?;
+    put_condition(writer, state.condition())?;
+    for (slot, original) in state.moves().iter().zip(&state.pokemon().moves) {
+        match (slot, original) {
+            (Some(slot), Some(original))
+                if ptr::eq(slot.known(), original.known())
+                    && slot.pp_ups() == original.pp_ups() =>
+            {
+                writer.put(slot.pp(), 6)?
+            }
+            (None, None) => writer.put(0u8, 6)?,
+            _ => return None,
+        }
+    }
+    Some(())
+}
+
+fn get_nv<'a>(reader: &mut Reader, pokemon: &'a Pokemon) -> NonVolatileState<'a> {
+    let hit_points = reader.get(STAT_BITS) as u16;
+    let condition = get_condition(reader);
+    let mut moves = pokemon.moves;
+    for slot in &mut moves {
+        let pp = reader.get(6) as u8;
+        if let Some(slot) = slot {
+            slot.set_pp(pp);
+        }
+    }
+    NonVolatileState::from_parts(pokemon, hit_points, condition, moves)
+}
+
+/// Get the stats of a state before any modifiers. Only the HP comes from the pokemon itself
+/// after transform.
+fn raw_stats(pokemon: &Pokemon, species: &Pokemon) -> [u16; 5] {
+    let stat = |stat| species.get_stat(stat);
+    [
+        pokemon.get_stat(Stat::HP),
+        stat(Stat::Attack),
+        stat(Stat::Defense),
+        stat(Stat::Special),
+        stat(Stat::Speed),
+    ]
+}
+
+/// Write the volatile part of a battle state.
+fn put_volatile(writer: &mut Writer, state: &BattleState, roster: &[&Pokemon]) -> Option<()> {
+    let plain = state.plain();
+    if plain.raw_stats != raw_stats(state.pokemon(), state.species())
+        || plain.stats[0] != plain.raw_stats[0]
+    {
+        return None;
+    }
+
+    writer.put(roster_index(roster, state.species())?, POKEMON_BITS)?;
+    for slot in state.moves() {
+        writer.known(slot.map(|slot| slot.known()))?;
+        writer.put(slot.map_or(0, |slot| (slot.pp_ups() << 6) | slot.pp()), 8)?;
+    }
+    for &kind in &plain.types {
+        let code = kind.map_or(0, |kind| TYPES.iter().position(|&t| t == kind).unwrap() + 1);
+        writer.put(code as u8, 4)?;
+    }
+    for &stat in &plain.stats[1..] {
+        writer.put(stat, STAT_BITS)?;
+    }
+    for modifier in &plain.modifiers {
+        writer.put((modifier.level() + 6) as u8, 4)?;
+    }
+    for &flag in &[
+        plain.transformed,
+        plain.seeded,
+        plain.flinched,
+        plain.must_recharge,
+        plain.reflect,
+        plain.light_screen,
+        plain.mist,
+        plain.focus_energy,
+    ] {
+        writer.flag(flag);
+    }
+    writer.put(plain.toxic_counter, 8)?;
+    writer.put(plain.confusion, 3)?;
+
+    writer.flag(plain.substitute.is_some());
+    writer.put(plain.substitute.unwrap_or(0), STAT_BITS)?;
+    let (turns, damage) = plain.bide.unwrap_or((0, 0));
+    writer.flag(plain.bide.is_some());
+    writer.put(turns, 2)?;
+    writer.put(damage, 16)?;
+
+    let (tag, known, byte, damage) = match state.lock() {
+        None => (0u8, None, 0, 0),
+        Some(&Lock::Thrash(used, turns)) => (1, Some(used), turns, 0),
+        Some(&Lock::Rage(used, accuracy)) => (2, Some(used), accuracy, 0),
+        Some(&Lock::Charging(used)) => (3, Some(used), 0, 0),
+        Some(&Lock::Trap(used, turns, damage)) => (4, Some(used), turns, damage),
+    };
+    writer.put(tag, 3)?;
+    writer.known(known)?;
+    writer.put(byte, 8)?;
+    writer.put(damage, 16)?;
+
+    writer.known(state.last_used())?;
+    writer.known(state.disabled().map(|(known, _)| known))?;
+    writer.put(state.disabled().map_or(0, |(_, turns)| turns), 4)
+}
+
+fn get_volatile<'a>(
+    reader: &mut Reader,
+    nv_state: NonVolatileState<'a>,
+    roster: &[&'a Pokemon],
+) -> BattleState<'a> {
+    let species = roster[reader.get(POKEMON_BITS) as usize];
+    let mut moves = [None; 4];
+    for slot in &mut moves {
+        let known = reader.known();
+        let pp = reader.get(8) as u8;
+        *slot = known.map(|known| {
+            let mut slot = MoveSlot::with_pp_ups(known, pp >> 6);
+            slot.set_pp(pp & 0x3f);
+            slot
+        });
+    }
+    let mut types = [None; 2];
+    for kind in &mut types {
+        *kind = match reader.get(4) as usize {
+            0 => None,
+            code => Some(TYPES[code - 1]),
+        };
+    }
+    let raw_stats = raw_stats(nv_state.pokemon(), species);
+    let mut stats = raw_stats;
+    for stat in &mut stats[1..] {
+        *stat = reader.get(STAT_BITS) as u16;
+    }
+    let mut modifiers = [Modifier::default(); 7];
+    for modifier in &mut modifiers {
+        *modifier = Modifier::from(reader.get(4) as i8 - 6);
+    }
+    let mut flags = [false; 8];
+    for flag in &mut flags {
+        *flag = reader.flag();
+    }
+    let [transformed, seeded, flinched, must_recharge, reflect, light_screen, mist, focus_energy] =
+        flags;
+    let toxic_counter = reader.get(8) as u16;
+    let confusion = reader.get(3) as u8;
+
+    let has_substitute = reader.flag();
+    let substitute = Some(reader.get(STAT_BITS) as u16).filter(|_| has_substitute);
+    let is_biding = reader.flag();
+    let turns = reader.get(2) as u8;
+    let damage = reader.get(16) as u16;
+    let bide = Some((turns, damage)).filter(|_| is_biding);
+
+    let tag = reader.get(3);
+    let known = reader.known();
+    let byte = reader.get(8) as u8;
+    let damage = reader.get(16) as u16;
+    let lock = known.map(|used| match tag {
+        1 => Lock::Thrash(used, byte),
+        2 => Lock::Rage(used, byte),
+        3 => Lock::Charging(used),
+        _ => Lock::Trap(used, byte, damage),
+    });
+
+    let last_used = reader.known();
+    let disabled = reader.known();
+    let turns = reader.get(4) as u8;
+
+    let plain = PlainState {
+        types,
+        transformed,
+        raw_stats,
+        stats,
+        modifiers,
+        toxic_counter,
+        seeded,
+        confusion,
+        flinched,
+        must_recharge,
+        substitute,
+        bide,
+        reflect,
+        light_screen,
+        mist,
+        focus_energy,
+    };
+    let disabled = disabled.map(|known| (known, turns));
+    BattleState::from_parts(nv_state, species, moves, lock, last_used, disabled, plain)
+}
+
+/// Compact copy of a `NonVolatileState`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct CompactNonVolatile([u64; 1]);
+
+impl CompactNonVolatile {
+    /// Create a compact copy of a state.
+    ///
+    /// Returns None if the pokemon isn't in the roster, if its moves differ from those of the
+    /// pokemon, or if its hit points don't fit.
+    pub fn new(state: &NonVolatileState, roster: &[&Pokemon]) -> Option<Self> {
+        let mut words = [0; 1];
+        let mut writer = Writer::new(&mut words);
+        writer.put(roster_index(roster, state.pokemon())?, POKEMON_BITS)?;
+        put_nv(&mut writer, state)?;
+        Some(CompactNonVolatile(words))
+    }
+
+    /// Get the full state back, using the same roster it was created with.
+    pub fn to_state<'a>(&self, roster: &[&'a Pokemon]) -> NonVolatileState<'a> {
+        let mut reader = Reader::new(&self.0, 0);
+        let pokemon = roster[reader.get(POKEMON_BITS) as usize];
+        get_nv(&mut reader, pokemon)
+    }
+
+    pub fn hit_points(&self) -> u16 {
+        Reader::new(&self.0, POKEMON_BITS as usize).get(STAT_BITS) as u16
+    }
+}
+
+/// Compact copy of a `BattleState`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CompactState([u64; 5]);
+
+impl CompactState {
+    /// Create a compact copy of a state.
+    ///
+    /// Returns None if a pokemon isn't in the roster, if the state refers to a move that isn't in
+    /// the movedex, or if any of its values don't fit.
+    pub fn new(state: &BattleState, roster: &[&Pokemon]) -> Option<Self> {
+        let mut words = [0; 5];
+        let mut writer = Writer::new(&mut words);
+        writer.put(roster_index(roster, state.pokemon())?, POKEMON_BITS)?;
+        put_nv(&mut writer, state.nv_state())?;
+        put_volatile(&mut writer, state, roster)?;
+        debug_assert_eq!(STATE_BITS, writer.position);
+        Some(CompactState(words))
+    }
+
+    /// Get the full state back, using the same roster it was created with.
+    pub fn to_state<'a>(&self, roster: &[&'a Pokemon]) -> BattleState<'a> {
+        read_state(&mut Reader::new(&self.0, 0), roster)
+    }
+
+    pub fn hit_points(&self) -> u16 {
+        Reader::new(&self.0, POKEMON_BITS as usize).get(STAT_BITS) as u16
+    }
+}
+
+fn read_state<'a>(reader: &mut Reader, roster: &[&'a Pokemon]) -> BattleState<'a> {
+    let pokemon = roster[reader.get(POKEMON_BITS) as usize];
+    let nv_state = get_nv(reader, pokemon);
+    get_volatile(reader, nv_state, roster)
+}
+
+/// Compact copy of a `Battle`, with teams of up to six pokemon.
+///
+/// This holds the states of both active pokemon, followed by the rest of the battle and the
+/// other members of both teams.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CompactBattle([u64; 16]);
+
+impl CompactBattle {
+    /// Create a compact copy of a battle.
+    ///
+    /// Returns None if a team has more than six pokemon, if the battle refers to a move that
+    /// isn't in the movedex, or if any of its values don't fit.
+    pub fn new(battle: &Battle) -> Option<Self> {
+        if (0..2).any(|side| battle.team_size(side) > MAX_TEAM_SIZE) {
+            return None;
+        }
+
+        let roster: Vec<_> = (0..2)
+            .flat_map(|side| (0..battle.team_size(side)).map(move |i| (side, i)))
+            .map(|(side, index)| battle.member(side, index).pokemon())
+            .collect();
+
+        let mut words = [0; 16];
+        for side in 0..2 {
+            let state = CompactState::new(battle.side(side), &roster)?;
+            let mut writer = Writer::new(&mut words);
+            writer.skip(side * STATE_BITS);
+            copy(&mut Reader::new(&state.0, 0), &mut writer, STATE_BITS);
+        }
+
+        let mut writer = Writer::new(&mut words);
+        writer.skip(2 * STATE_BITS);
+        for side in 0..2 {
+            writer.put(battle.team_size(side) as u8, 3)?;
+            writer.put(battle.active(side) as u8, 3)?;
+        }
+        writer.put(battle.last_damage(), 16)?;
+        writer.known(battle.last_move())?;
+        writer.flag(battle.ruleset() == Ruleset::Stadium);
+
+        // The entries of the active pokemon are in their states already.
+        for side in 0..2 {
+            for index in (0..MAX_TEAM_SIZE).filter(|&i| i != battle.active(side)) {
+                if index < battle.team_size(side) {
+                    put_nv(&mut writer, battle.member(side, index))?;
+                } else {
+                    writer.skip(NV_BITS);
+                }
+            }
+        }
+        Some(CompactBattle(words))
+    }
+
+    /// Get the full battle back, given the teams it was created with.
+    pub fn to_battle<'a>(&self, first: &'a [Pokemon], second: &'a [Pokemon]) -> Battle<'a> {
+        let roster: Vec<_> = first.iter().chain(second).collect();
+        let mut reader = Reader::new(&self.0, 0);
+        let sides = [
+            read_state(&mut reader, &roster),
+            read_state(&mut reader, &roster),
+        ];
+
+        let mut sizes = [0; 2];
+        let mut active = [0; 2];
+        for side in 0..2 {
+            sizes[side] = reader.get(3) as usize;
+            active[side] = reader.get(3) as usize;
+        }
+        let last_damage = reader.get(16) as u16;
+        let last_move = reader.known();
+        let ruleset = if reader.flag() {
+            Ruleset::Stadium
+        } else {
+            Ruleset::Cartridge
+        };
+
+        let mut teams = [Vec::new(), Vec::new()];
+        let offsets = [0, first.len()];
+        for side in 0..2 {
+            for index in (0..MAX_TEAM_SIZE).filter(|&i| i != active[side]) {
+                if index < sizes[side] {
+                    let member = get_nv(&mut reader, roster[offsets[side] + index]);
+                    teams[side].push(member);
+                } else {
+                    reader.skip(NV_BITS);
+                }
+            }
+            let current = sides[side].nv_state().clone();
+            teams[side].insert(active[side], current);
+        }
+
+        Battle::from_parts(sides, teams, active, last_damage, last_move, ruleset)
+    }
+
+    /// Get the state of the active pokemon of a side.
+    pub fn side(&self, side: usize) -> CompactState {
+        let mut words = [0; 5];
+        let mut reader = Reader::new(&self.0, side * STATE_BITS);
+        copy(&mut reader, &mut Writer::new(&mut words), STATE_BITS);
+        CompactState(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::player::{Player, RandomPlayer};
+    use crate::simulation::choose_replacement;
+
+    use super::*;
+
+    #[test]
+    fn test_size() {
+        // A whole battle fits in two cache lines.
+        assert_eq!(8, size_of::<CompactNonVolatile>());
+        assert_eq!(40, size_of::<CompactState>());
+        assert_eq!(128, size_of::<CompactBattle>());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut pokemon = Pokemon::default();
+        pokemon.moves[0] = Some(MoveSlot::new(&movedex::TACKLE));
+        pokemon.moves[1] = Some(MoveSlot::with_pp_ups(&movedex::THUNDER_WAVE, 3));
+        pokemon.moves[2] = Some(MoveSlot::new(&movedex::TRANSFORM));
+        pokemon.moves[3] = Some(MoveSlot::new(&movedex::WRAP));
+        let first = [pokemon, Pokemon::default()];
+        let second = [Pokemon::default()];
+        let mut battle = Battle::with_teams(&first, &second, Ruleset::Cartridge);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut players = [
+            RandomPlayer::new(StdRng::seed_from_u64(1)),
+            RandomPlayer::new(StdRng::seed_from_u64(2)),
+        ];
+        for _ in 0..50 {
+            let compact = CompactBattle::new(&battle).unwrap();
+            let restored = compact.to_battle(&first, &second);
+            assert!(restored == battle);
+            assert_eq!(compact, CompactBattle::new(&restored).unwrap());
+            if battle.is_over() {
+                break;
+            }
+
+            let actions = [0, 1].map(|side| players[side].choose(&battle, side));
+            battle.turn(&mut rng, actions);
+            for (side, player) in players.iter_mut().enumerate() {
+                if battle.must_replace(side) {
+                    if let Some(index) = choose_replacement(player, &battle, side) {
+                        battle.replace(side, index);
+                    }
+                }
+            }
+        }
+    }
+}
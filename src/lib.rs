@@ -1,6 +1,16 @@
 pub mod battle;
+pub mod calc;
+pub mod chance;
+pub mod compact;
+pub mod mcts;
+pub mod movedex;
 pub mod moves;
+pub mod player;
+pub mod pokedex;
 pub mod pokemon;
+pub mod simulation;
+pub mod solver;
 pub mod stats;
+pub mod team;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,285 @@
+//! Player based on Monte Carlo tree search.
+//!
+//! Both sides choose at the same time, so this uses decoupled UCT: every node keeps separate
+//! statistics for the actions of either side, and both sides pick their action independently.
+//! The tree is open loop, meaning a node stands for the actions taken to reach it, not for a
+//! single state. Every iteration plays the random outcomes of those actions again.
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::battle::{Action, Battle};
+use crate::player::Player;
+use crate::solver::{choices, estimate, result};
+
+#[derive(Copy, Clone)]
+struct Statistics {
+    choice: Option<Action>,
+    visits: u32,
+    /// Total reward for the side making the choice.
+    reward: f64,
+}
+
+#[derive(Default)]
+struct Node {
+    visits: u32,
+    statistics: [Vec<Statistics>; 2],
+    children: HashMap<[Option<Action>; 2], usize>,
+}
+
+impl Node {
+    /// Pick the choice for a side with the highest upper confidence bound, out of the choices that
+    /// are available right now. Untried choices go first, in order. Returns the index of the
+    /// statistics for the choice.
+    fn select(&mut self, side: usize, available: &[Option<Action>], exploration: f64) -> usize {
+        let statistics = &mut self.statistics[side];
+        let mut indices = Vec::with_capacity(available.len());
+        for &choice in available {
+            match statistics.iter().position(|s| s.choice == choice) {
+                Some(index) => indices.push(index),
+                None => {
+                    indices.push(statistics.len());
+                    statistics.push(Statistics {
+                        choice,
+                        visits: 0,
+                        reward: 0.0,
+                    });
+                }
+            }
+        }
+
+        if let Some(&index) = indices.iter().find(|&&i| statistics[i].visits == 0) {
+            return index;
+        }
+
+        let log_visits = f64::from(self.visits).ln();
+        let bound = |s: &Statistics| {
+            let visits = f64::from(s.visits);
+            s.reward / visits + exploration * (log_visits / visits).sqrt()
+        };
+
+        // Ties go to the earliest choice.
+        let mut best = indices[0];
+        for &index in &indices[1..] {
+            if bound(&statistics[index]) > bound(&statistics[best]) {
+                best = index;
+            }
+        }
+        best
+    }
+}
+
+/// 64-bit FNV-1a hash, so seeds don't depend on the hasher of the standard library, whose
+/// algorithm may change between releases.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Player that searches for the best action with Monte Carlo tree search.
+///
+/// Every iteration walks down the tree from the current state, adds a node, and plays the rest of
+/// the battle with random actions. The chosen action is the one tried most often.
+///
+/// Every choice starts a new search, with random numbers seeded from the seed, the battle and the
+/// side. So with the same settings, the player makes the same choice in the same state, no matter
+/// which choices it made before.
+pub struct MctsPlayer {
+    /// Number of iterations per choice.
+    pub iterations: u32,
+    /// How much to favor actions that have been tried less often.
+    pub exploration: f64,
+    /// Number of turns to play with random actions after leaving the tree. Battles that last
+    /// longer are valued by the hit points both sides have left.
+    pub rollout_turns: u32,
+    pub seed: u64,
+}
+
+impl MctsPlayer {
+    /// Create a player with the default exploration and rollout length.
+    pub fn new(iterations: u32, seed: u64) -> Self {
+        MctsPlayer {
+            iterations,
+            exploration: std::f64::consts::SQRT_2,
+            rollout_turns: 50,
+            seed,
+        }
+    }
+
+    /// Get the random numbers for choosing in a state.
+    fn rand(&self, battle: &Battle, side: usize) -> StdRng {
+        let mut hasher = Fnv1a::default();
+        (self.seed, battle, side).hash(&mut hasher);
+        StdRng::seed_from_u64(hasher.finish())
+    }
+
+    /// Play one iteration of the search, and return the value of the battle it reached for the
+    /// first side.
+    fn iterate(&self, rand: &mut StdRng, nodes: &mut Vec<Node>, root: &Battle) -> f64 {
+        let mut battle = root.clone();
+        let mut path = Vec::new();
+        let mut node = 0;
+
+        let value = loop {
+            if battle.is_over() {
+                break result(&battle);
+            }
+
+            let available = choices(&battle);
+            let selected =
+                [0, 1].map(|side| nodes[node].select(side, &available[side], self.exploration));
+            path.push((node, selected));
+
+            let chosen = [0, 1].map(|side| nodes[node].statistics[side][selected[side]].choice);
+            advance(rand, &mut battle, chosen);
+
+            match nodes[node].children.get(&chosen) {
+                Some(&child) => node = child,
+                None => {
+                    let child = nodes.len();
+                    nodes[node].children.insert(chosen, child);
+                    nodes.push(Node::default());
+                    break self.rollout(rand, &mut battle);
+                }
+            }
+        };
+
+        for (node, selected) in path {
+            let node = &mut nodes[node];
+            node.visits += 1;
+            for (side, &index) in selected.iter().enumerate() {
+                let statistics = &mut node.statistics[side][index];
+                statistics.visits += 1;
+                statistics.reward += if side == 0 { value } else { 1.0 - value };
+            }
+        }
+
+        value
+    }
+
+    /// Play random actions until the battle is over or the turn limit is reached, and return the
+    /// value of the battle for the first side.
+    fn rollout(&self, rand: &mut StdRng, battle: &mut Battle) -> f64 {
+        let mut turns = 0;
+        while !battle.is_over() && turns < self.rollout_turns {
+            let available = choices(battle);
+            let chosen = [0, 1].map(|side| *available[side].choose(rand).unwrap());
+            if (0..2).all(|side| !battle.must_replace(side)) {
+                turns += 1;
+            }
+            advance(rand, battle, chosen);
+        }
+
+        if battle.is_over() {
+            result(battle)
+        } else {
+            estimate(battle)
+        }
+    }
+}
+
+/// Apply the choices of both sides, either playing a turn or replacing fainted pokemon.
+fn advance(rand: &mut StdRng, battle: &mut Battle, chosen: [Option<Action>; 2]) {
+    match chosen {
+        [Some(first), Some(second)] if (0..2).all(|side| !battle.must_replace(side)) => {
+            battle.turn(rand, [first, second]);
+        }
+        _ => {
+            for (side, choice) in chosen.iter().enumerate() {
+                if let Some(Action::Switch(index)) = *choice {
+                    battle.replace(side, index);
+                }
+            }
+        }
+    }
+}
+
+impl Player for MctsPlayer {
+    fn choose(&mut self, battle: &Battle, side: usize) -> Action {
+        let mut rand = self.rand(battle, side);
+        let mut nodes = vec![Node::default()];
+        for _ in 0..self.iterations {
+            self.iterate(&mut rand, &mut nodes, battle);
+        }
+
+        // Pick the action tried most often, ties go to the earliest action.
+        let available = battle.available_actions(side);
+        let visits = |action: &Action| {
+            let statistics = nodes[0].statistics[side].iter();
+            let mut matching = statistics.filter(|s| s.choice == Some(*action));
+            matching.next().map_or(0, |s| s.visits)
+        };
+        let mut best = *available.first().expect("No actions available");
+        for action in &available[1..] {
+            if visits(action) > visits(&best) {
+                best = *action;
+            }
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::battle::Ruleset;
+    use crate::movedex;
+    use crate::moves::MoveSlot;
+    use crate::pokemon::Pokemon;
+
+    use super::*;
+
+    #[test]
+    fn test_fnv1a() {
+        let mut hasher = Fnv1a::default();
+        assert_eq!(0xcbf2_9ce4_8422_2325, hasher.finish());
+        hasher.write(b"a");
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hasher.finish());
+    }
+
+    #[test]
+    fn test_mcts_player() {
+        let mut pokemon = Pokemon {
+            level: 20,
+            ..Default::default()
+        };
+        pokemon.moves[0] = Some(MoveSlot::new(&movedex::GROWL));
+        pokemon.moves[1] = Some(MoveSlot::new(&movedex::SONIC_BOOM));
+        let team = [pokemon];
+        let battle = Battle::with_teams(&team, &team, Ruleset::Cartridge);
+
+        let mut player = MctsPlayer::new(200, 42);
+        let chosen = player.choose(&battle, 0);
+        assert!(matches!(chosen, Action::UseMove(used) if used.name() == "Sonic Boom"));
+
+        // The same seed gives the same choice in the same state, whatever was chosen before.
+        let mut other = MctsPlayer::new(200, 42);
+        let second = other.choose(&battle, 1);
+        assert_eq!(chosen, other.choose(&battle, 0));
+        assert_eq!(second, player.choose(&battle, 1));
+        assert_eq!(chosen, player.choose(&battle, 0));
+
+        let team = [Pokemon::default(), Pokemon::default()];
+        let mut battle = Battle::with_teams(&team, &team, Ruleset::Cartridge);
+        battle.side_mut(0).damage_direct(999);
+        assert_eq!(Action::Switch(1), player.choose(&battle, 0));
+    }
+}
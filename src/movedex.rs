@@ -0,0 +1,944 @@
+//! Definitions for the moves available in generation one.
+//!
+//! Moves are listed in the order of their index number in the games.
+use std::num::NonZeroU8;
+use std::ptr;
+
+use crate::moves::{Move, MoveEffect};
+use crate::stats::Stat;
+use crate::types::Type;
+use crate::utils::normalize_name;
+
+/// Get the position of a move in `MOVES`, or None if it isn't one of the moves listed there.
+pub fn index(known: &Move) -> Option<usize> {
+    MOVES.iter().position(|&m| ptr::eq(m, known))
+}
+
+/// Find a move by name, ignoring case, spaces and punctuation.
+pub fn find(name: &str) -> Option<&'static Move> {
+    let name = normalize_name(name);
+    MOVES
+        .iter()
+        .copied()
+        .find(|m| normalize_name(m.name()) == name)
+}
+
+/// Recoil effect dealing the given fraction of the damage back to the user.
+const fn recoil(divider: u8) -> MoveEffect {
+    MoveEffect::Recoil(NonZeroU8::new(divider).unwrap())
+}
+
+pub static POUND: Move = Move::new("Pound", Type::Normal, 40, 255, 35, MoveEffect::Normal);
+pub static KARATE_CHOP: Move = Move::new(
+    "Karate Chop",
+    Type::Normal,
+    50,
+    255,
+    25,
+    MoveEffect::HighCrit,
+);
+pub static DOUBLE_SLAP: Move = Move::new(
+    "Double Slap",
+    Type::Normal,
+    15,
+    216,
+    10,
+    MoveEffect::MultiHit,
+);
+pub static COMET_PUNCH: Move = Move::new(
+    "Comet Punch",
+    Type::Normal,
+    18,
+    216,
+    15,
+    MoveEffect::MultiHit,
+);
+pub static MEGA_PUNCH: Move =
+    Move::new("Mega Punch", Type::Normal, 80, 216, 20, MoveEffect::Normal);
+pub static PAY_DAY: Move = Move::new("Pay Day", Type::Normal, 40, 255, 20, MoveEffect::Normal);
+pub static FIRE_PUNCH: Move = Move::new(
+    "Fire Punch",
+    Type::Fire,
+    75,
+    255,
+    15,
+    MoveEffect::BurnChance(26),
+);
+pub static ICE_PUNCH: Move = Move::new(
+    "Ice Punch",
+    Type::Ice,
+    75,
+    255,
+    15,
+    MoveEffect::FreezeChance(26),
+);
+pub static THUNDER_PUNCH: Move = Move::new(
+    "Thunder Punch",
+    Type::Electric,
+    75,
+    255,
+    15,
+    MoveEffect::ParalyzeChance(26),
+);
+pub static SCRATCH: Move = Move::new("Scratch", Type::Normal, 40, 255, 35, MoveEffect::Normal);
+pub static VICE_GRIP: Move = Move::new("Vice Grip", Type::Normal, 55, 255, 30, MoveEffect::Normal);
+pub static GUILLOTINE: Move = Move::new("Guillotine", Type::Normal, 0, 76, 5, MoveEffect::OneHitKO);
+pub static RAZOR_WIND: Move = Move::new(
+    "Razor Wind",
+    Type::Normal,
+    80,
+    191,
+    10,
+    MoveEffect::Charge(false),
+);
+pub static SWORDS_DANCE: Move = Move::new(
+    "Swords Dance",
+    Type::Normal,
+    0,
+    0,
+    30,
+    MoveEffect::StatUp(Stat::Attack, 2),
+);
+pub static CUT: Move = Move::new("Cut", Type::Normal, 50, 242, 30, MoveEffect::Normal);
+pub static GUST: Move = Move::new("Gust", Type::Normal, 40, 255, 35, MoveEffect::Normal);
+pub static WING_ATTACK: Move =
+    Move::new("Wing Attack", Type::Flying, 35, 255, 35, MoveEffect::Normal);
+pub static WHIRLWIND: Move = Move::new("Whirlwind", Type::Normal, 0, 216, 20, MoveEffect::NoEffect);
+pub static FLY: Move = Move::new("Fly", Type::Flying, 70, 242, 15, MoveEffect::Charge(true));
+pub static BIND: Move = Move::new("Bind", Type::Normal, 15, 191, 20, MoveEffect::Trap);
+pub static SLAM: Move = Move::new("Slam", Type::Normal, 80, 191, 20, MoveEffect::Normal);
+pub static VINE_WHIP: Move = Move::new("Vine Whip", Type::Grass, 35, 255, 10, MoveEffect::Normal);
+pub static STOMP: Move = Move::new(
+    "Stomp",
+    Type::Normal,
+    65,
+    255,
+    20,
+    MoveEffect::FlinchChance(77),
+);
+pub static DOUBLE_KICK: Move = Move::new(
+    "Double Kick",
+    Type::Fighting,
+    30,
+    255,
+    30,
+    MoveEffect::DoubleHit,
+);
+pub static MEGA_KICK: Move = Move::new("Mega Kick", Type::Normal, 120, 191, 5, MoveEffect::Normal);
+pub static JUMP_KICK: Move = Move::new(
+    "Jump Kick",
+    Type::Fighting,
+    70,
+    242,
+    25,
+    MoveEffect::JumpKick,
+);
+pub static ROLLING_KICK: Move = Move::new(
+    "Rolling Kick",
+    Type::Fighting,
+    60,
+    216,
+    15,
+    MoveEffect::FlinchChance(77),
+);
+pub static SAND_ATTACK: Move = Move::new(
+    "Sand Attack",
+    Type::Normal,
+    0,
+    255,
+    15,
+    MoveEffect::StatDown(Stat::Accuracy, 1),
+);
+pub static HEADBUTT: Move = Move::new(
+    "Headbutt",
+    Type::Normal,
+    70,
+    255,
+    15,
+    MoveEffect::FlinchChance(77),
+);
+pub static HORN_ATTACK: Move =
+    Move::new("Horn Attack", Type::Normal, 65, 255, 25, MoveEffect::Normal);
+pub static FURY_ATTACK: Move = Move::new(
+    "Fury Attack",
+    Type::Normal,
+    15,
+    216,
+    20,
+    MoveEffect::MultiHit,
+);
+pub static HORN_DRILL: Move = Move::new("Horn Drill", Type::Normal, 0, 76, 5, MoveEffect::OneHitKO);
+pub static TACKLE: Move = Move::new("Tackle", Type::Normal, 35, 242, 35, MoveEffect::Normal);
+pub static BODY_SLAM: Move = Move::new(
+    "Body Slam",
+    Type::Normal,
+    85,
+    255,
+    15,
+    MoveEffect::ParalyzeChance(77),
+);
+pub static WRAP: Move = Move::new("Wrap", Type::Normal, 15, 216, 20, MoveEffect::Trap);
+pub static TAKE_DOWN: Move = Move::new("Take Down", Type::Normal, 90, 216, 20, recoil(4));
+pub static THRASH: Move = Move::new("Thrash", Type::Normal, 90, 255, 20, MoveEffect::Thrash);
+pub static DOUBLE_EDGE: Move = Move::new("Double-Edge", Type::Normal, 100, 255, 15, recoil(4));
+pub static TAIL_WHIP: Move = Move::new(
+    "Tail Whip",
+    Type::Normal,
+    0,
+    255,
+    30,
+    MoveEffect::StatDown(Stat::Defense, 1),
+);
+pub static POISON_STING: Move = Move::new(
+    "Poison Sting",
+    Type::Poison,
+    15,
+    255,
+    35,
+    MoveEffect::PoisonChance(52),
+);
+pub static TWINEEDLE: Move = Move::new("Twineedle", Type::Bug, 25, 255, 20, MoveEffect::Twineedle);
+pub static PIN_MISSILE: Move =
+    Move::new("Pin Missile", Type::Bug, 14, 216, 20, MoveEffect::MultiHit);
+pub static LEER: Move = Move::new(
+    "Leer",
+    Type::Normal,
+    0,
+    255,
+    30,
+    MoveEffect::StatDown(Stat::Defense, 1),
+);
+pub static BITE: Move = Move::new(
+    "Bite",
+    Type::Normal,
+    60,
+    255,
+    25,
+    MoveEffect::FlinchChance(26),
+);
+pub static GROWL: Move = Move::new(
+    "Growl",
+    Type::Normal,
+    0,
+    255,
+    40,
+    MoveEffect::StatDown(Stat::Attack, 1),
+);
+pub static ROAR: Move = Move::new("Roar", Type::Normal, 0, 255, 20, MoveEffect::NoEffect);
+pub static SING: Move = Move::new("Sing", Type::Normal, 0, 140, 15, MoveEffect::Sleep);
+pub static SUPERSONIC: Move =
+    Move::new("Supersonic", Type::Normal, 0, 140, 20, MoveEffect::Confuse);
+pub static SONIC_BOOM: Move = Move::new(
+    "Sonic Boom",
+    Type::Normal,
+    0,
+    229,
+    20,
+    MoveEffect::FixedDamage(20),
+);
+pub static DISABLE: Move = Move::new("Disable", Type::Normal, 0, 140, 20, MoveEffect::Disable);
+pub static ACID: Move = Move::new(
+    "Acid",
+    Type::Poison,
+    40,
+    255,
+    30,
+    MoveEffect::StatDownChance(Stat::Defense, 85),
+);
+pub static EMBER: Move = Move::new("Ember", Type::Fire, 40, 255, 25, MoveEffect::BurnChance(26));
+pub static FLAMETHROWER: Move = Move::new(
+    "Flamethrower",
+    Type::Fire,
+    95,
+    255,
+    15,
+    MoveEffect::BurnChance(26),
+);
+pub static MIST: Move = Move::new("Mist", Type::Ice, 0, 0, 30, MoveEffect::Mist);
+pub static WATER_GUN: Move = Move::new("Water Gun", Type::Water, 40, 255, 25, MoveEffect::Normal);
+pub static HYDRO_PUMP: Move = Move::new("Hydro Pump", Type::Water, 120, 204, 5, MoveEffect::Normal);
+pub static SURF: Move = Move::new("Surf", Type::Water, 95, 255, 15, MoveEffect::Normal);
+pub static ICE_BEAM: Move = Move::new(
+    "Ice Beam",
+    Type::Ice,
+    95,
+    255,
+    10,
+    MoveEffect::FreezeChance(26),
+);
+pub static BLIZZARD: Move = Move::new(
+    "Blizzard",
+    Type::Ice,
+    120,
+    229,
+    5,
+    MoveEffect::FreezeChance(26),
+);
+pub static PSYBEAM: Move = Move::new(
+    "Psybeam",
+    Type::Psychic,
+    65,
+    255,
+    20,
+    MoveEffect::ConfuseChance(25),
+);
+pub static BUBBLE_BEAM: Move = Move::new(
+    "Bubble Beam",
+    Type::Water,
+    65,
+    255,
+    20,
+    MoveEffect::StatDownChance(Stat::Speed, 85),
+);
+pub static AURORA_BEAM: Move = Move::new(
+    "Aurora Beam",
+    Type::Ice,
+    65,
+    255,
+    20,
+    MoveEffect::StatDownChance(Stat::Attack, 85),
+);
+pub static HYPER_BEAM: Move = Move::new(
+    "Hyper Beam",
+    Type::Normal,
+    150,
+    229,
+    5,
+    MoveEffect::HyperBeam,
+);
+pub static PECK: Move = Move::new("Peck", Type::Flying, 35, 255, 35, MoveEffect::Normal);
+pub static DRILL_PECK: Move =
+    Move::new("Drill Peck", Type::Flying, 80, 255, 20, MoveEffect::Normal);
+pub static SUBMISSION: Move = Move::new("Submission", Type::Fighting, 80, 204, 25, recoil(4));
+pub static LOW_KICK: Move = Move::new(
+    "Low Kick",
+    Type::Fighting,
+    50,
+    229,
+    20,
+    MoveEffect::FlinchChance(77),
+);
+pub static COUNTER: Move = Move::new("Counter", Type::Fighting, 0, 255, 20, MoveEffect::Counter);
+pub static SEISMIC_TOSS: Move = Move::new(
+    "Seismic Toss",
+    Type::Fighting,
+    0,
+    255,
+    20,
+    MoveEffect::LevelDamage,
+);
+pub static STRENGTH: Move = Move::new("Strength", Type::Normal, 80, 255, 15, MoveEffect::Normal);
+pub static ABSORB: Move = Move::new("Absorb", Type::Grass, 20, 255, 20, MoveEffect::Drain);
+pub static MEGA_DRAIN: Move = Move::new("Mega Drain", Type::Grass, 40, 255, 10, MoveEffect::Drain);
+pub static LEECH_SEED: Move =
+    Move::new("Leech Seed", Type::Grass, 0, 229, 10, MoveEffect::LeechSeed);
+pub static GROWTH: Move = Move::new(
+    "Growth",
+    Type::Normal,
+    0,
+    0,
+    40,
+    MoveEffect::StatUp(Stat::Special, 1),
+);
+pub static RAZOR_LEAF: Move =
+    Move::new("Razor Leaf", Type::Grass, 55, 242, 25, MoveEffect::HighCrit);
+pub static SOLAR_BEAM: Move = Move::new(
+    "Solar Beam",
+    Type::Grass,
+    120,
+    255,
+    10,
+    MoveEffect::Charge(false),
+);
+pub static POISON_POWDER: Move = Move::new(
+    "Poison Powder",
+    Type::Poison,
+    0,
+    191,
+    35,
+    MoveEffect::Poison,
+);
+pub static STUN_SPORE: Move =
+    Move::new("Stun Spore", Type::Grass, 0, 191, 30, MoveEffect::Paralyze);
+pub static SLEEP_POWDER: Move =
+    Move::new("Sleep Powder", Type::Grass, 0, 191, 15, MoveEffect::Sleep);
+pub static PETAL_DANCE: Move =
+    Move::new("Petal Dance", Type::Grass, 70, 255, 20, MoveEffect::Thrash);
+pub static STRING_SHOT: Move = Move::new(
+    "String Shot",
+    Type::Bug,
+    0,
+    242,
+    40,
+    MoveEffect::StatDown(Stat::Speed, 1),
+);
+pub static DRAGON_RAGE: Move = Move::new(
+    "Dragon Rage",
+    Type::Dragon,
+    0,
+    255,
+    10,
+    MoveEffect::FixedDamage(40),
+);
+pub static FIRE_SPIN: Move = Move::new("Fire Spin", Type::Fire, 15, 178, 15, MoveEffect::Trap);
+pub static THUNDER_SHOCK: Move = Move::new(
+    "Thunder Shock",
+    Type::Electric,
+    40,
+    255,
+    30,
+    MoveEffect::ParalyzeChance(26),
+);
+pub static THUNDERBOLT: Move = Move::new(
+    "Thunderbolt",
+    Type::Electric,
+    95,
+    255,
+    15,
+    MoveEffect::ParalyzeChance(26),
+);
+pub static THUNDER_WAVE: Move = Move::new(
+    "Thunder Wave",
+    Type::Electric,
+    0,
+    255,
+    20,
+    MoveEffect::Paralyze,
+);
+pub static THUNDER: Move = Move::new(
+    "Thunder",
+    Type::Electric,
+    120,
+    178,
+    10,
+    MoveEffect::ParalyzeChance(26),
+);
+pub static ROCK_THROW: Move = Move::new("Rock Throw", Type::Rock, 50, 165, 15, MoveEffect::Normal);
+pub static EARTHQUAKE: Move =
+    Move::new("Earthquake", Type::Ground, 100, 255, 10, MoveEffect::Normal);
+pub static FISSURE: Move = Move::new("Fissure", Type::Ground, 0, 76, 5, MoveEffect::OneHitKO);
+pub static DIG: Move = Move::new("Dig", Type::Ground, 100, 255, 10, MoveEffect::Charge(true));
+pub static TOXIC: Move = Move::new("Toxic", Type::Poison, 0, 216, 10, MoveEffect::Toxic);
+pub static CONFUSION: Move = Move::new(
+    "Confusion",
+    Type::Psychic,
+    50,
+    255,
+    25,
+    MoveEffect::ConfuseChance(25),
+);
+pub static PSYCHIC: Move = Move::new(
+    "Psychic",
+    Type::Psychic,
+    90,
+    255,
+    10,
+    MoveEffect::StatDownChance(Stat::Special, 85),
+);
+pub static HYPNOSIS: Move = Move::new("Hypnosis", Type::Psychic, 0, 153, 20, MoveEffect::Sleep);
+pub static MEDITATE: Move = Move::new(
+    "Meditate",
+    Type::Psychic,
+    0,
+    0,
+    40,
+    MoveEffect::StatUp(Stat::Attack, 1),
+);
+pub static AGILITY: Move = Move::new(
+    "Agility",
+    Type::Psychic,
+    0,
+    0,
+    30,
+    MoveEffect::StatUp(Stat::Speed, 2),
+);
+pub static QUICK_ATTACK: Move = Move::new(
+    "Quick Attack",
+    Type::Normal,
+    40,
+    255,
+    30,
+    MoveEffect::Priority,
+);
+pub static RAGE: Move = Move::new("Rage", Type::Normal, 20, 255, 20, MoveEffect::Rage);
+pub static TELEPORT: Move = Move::new("Teleport", Type::Psychic, 0, 0, 20, MoveEffect::NoEffect);
+pub static NIGHT_SHADE: Move = Move::new(
+    "Night Shade",
+    Type::Ghost,
+    0,
+    255,
+    15,
+    MoveEffect::LevelDamage,
+);
+pub static MIMIC: Move = Move::new("Mimic", Type::Normal, 0, 255, 10, MoveEffect::Mimic);
+pub static SCREECH: Move = Move::new(
+    "Screech",
+    Type::Normal,
+    0,
+    216,
+    40,
+    MoveEffect::StatDown(Stat::Defense, 2),
+);
+pub static DOUBLE_TEAM: Move = Move::new(
+    "Double Team",
+    Type::Normal,
+    0,
+    0,
+    15,
+    MoveEffect::StatUp(Stat::Evasion, 1),
+);
+pub static RECOVER: Move = Move::new("Recover", Type::Normal, 0, 0, 20, MoveEffect::Recover);
+pub static HARDEN: Move = Move::new(
+    "Harden",
+    Type::Normal,
+    0,
+    0,
+    30,
+    MoveEffect::StatUp(Stat::Defense, 1),
+);
+pub static MINIMIZE: Move = Move::new(
+    "Minimize",
+    Type::Normal,
+    0,
+    0,
+    20,
+    MoveEffect::StatUp(Stat::Evasion, 1),
+);
+pub static SMOKESCREEN: Move = Move::new(
+    "Smokescreen",
+    Type::Normal,
+    0,
+    255,
+    20,
+    MoveEffect::StatDown(Stat::Accuracy, 1),
+);
+pub static CONFUSE_RAY: Move =
+    Move::new("Confuse Ray", Type::Ghost, 0, 255, 10, MoveEffect::Confuse);
+pub static WITHDRAW: Move = Move::new(
+    "Withdraw",
+    Type::Water,
+    0,
+    0,
+    40,
+    MoveEffect::StatUp(Stat::Defense, 1),
+);
+pub static DEFENSE_CURL: Move = Move::new(
+    "Defense Curl",
+    Type::Normal,
+    0,
+    0,
+    40,
+    MoveEffect::StatUp(Stat::Defense, 1),
+);
+pub static BARRIER: Move = Move::new(
+    "Barrier",
+    Type::Psychic,
+    0,
+    0,
+    30,
+    MoveEffect::StatUp(Stat::Defense, 2),
+);
+pub static LIGHT_SCREEN: Move = Move::new(
+    "Light Screen",
+    Type::Psychic,
+    0,
+    0,
+    30,
+    MoveEffect::LightScreen,
+);
+pub static HAZE: Move = Move::new("Haze", Type::Ice, 0, 0, 30, MoveEffect::Haze);
+pub static REFLECT: Move = Move::new("Reflect", Type::Psychic, 0, 0, 20, MoveEffect::Reflect);
+pub static FOCUS_ENERGY: Move = Move::new(
+    "Focus Energy",
+    Type::Normal,
+    0,
+    0,
+    30,
+    MoveEffect::FocusEnergy,
+);
+pub static BIDE: Move = Move::new("Bide", Type::Normal, 0, 0, 10, MoveEffect::Bide);
+pub static METRONOME: Move = Move::new("Metronome", Type::Normal, 0, 0, 10, MoveEffect::Metronome);
+pub static MIRROR_MOVE: Move = Move::new(
+    "Mirror Move",
+    Type::Flying,
+    0,
+    0,
+    20,
+    MoveEffect::MirrorMove,
+);
+pub static SELF_DESTRUCT: Move = Move::new(
+    "Self-Destruct",
+    Type::Normal,
+    130,
+    255,
+    5,
+    MoveEffect::SelfKO,
+);
+pub static EGG_BOMB: Move = Move::new("Egg Bomb", Type::Normal, 100, 191, 10, MoveEffect::Normal);
+pub static LICK: Move = Move::new(
+    "Lick",
+    Type::Ghost,
+    20,
+    255,
+    30,
+    MoveEffect::ParalyzeChance(77),
+);
+pub static SMOG: Move = Move::new(
+    "Smog",
+    Type::Poison,
+    20,
+    178,
+    20,
+    MoveEffect::PoisonChance(103),
+);
+pub static SLUDGE: Move = Move::new(
+    "Sludge",
+    Type::Poison,
+    65,
+    255,
+    20,
+    MoveEffect::PoisonChance(103),
+);
+pub static BONE_CLUB: Move = Move::new(
+    "Bone Club",
+    Type::Ground,
+    65,
+    216,
+    20,
+    MoveEffect::FlinchChance(26),
+);
+pub static FIRE_BLAST: Move = Move::new(
+    "Fire Blast",
+    Type::Fire,
+    120,
+    216,
+    5,
+    MoveEffect::BurnChance(77),
+);
+pub static WATERFALL: Move = Move::new("Waterfall", Type::Water, 80, 255, 15, MoveEffect::Normal);
+pub static CLAMP: Move = Move::new("Clamp", Type::Water, 35, 191, 10, MoveEffect::Trap);
+pub static SWIFT: Move = Move::new("Swift", Type::Normal, 60, 0, 20, MoveEffect::Normal);
+pub static SKULL_BASH: Move = Move::new(
+    "Skull Bash",
+    Type::Normal,
+    100,
+    255,
+    15,
+    MoveEffect::Charge(false),
+);
+pub static SPIKE_CANNON: Move = Move::new(
+    "Spike Cannon",
+    Type::Normal,
+    20,
+    255,
+    15,
+    MoveEffect::MultiHit,
+);
+pub static CONSTRICT: Move = Move::new(
+    "Constrict",
+    Type::Normal,
+    10,
+    255,
+    35,
+    MoveEffect::StatDownChance(Stat::Speed, 85),
+);
+pub static AMNESIA: Move = Move::new(
+    "Amnesia",
+    Type::Psychic,
+    0,
+    0,
+    20,
+    MoveEffect::StatUp(Stat::Special, 2),
+);
+pub static KINESIS: Move = Move::new(
+    "Kinesis",
+    Type::Psychic,
+    0,
+    204,
+    15,
+    MoveEffect::StatDown(Stat::Accuracy, 1),
+);
+pub static SOFT_BOILED: Move =
+    Move::new("Soft-Boiled", Type::Normal, 0, 0, 10, MoveEffect::Recover);
+pub static HIGH_JUMP_KICK: Move = Move::new(
+    "High Jump Kick",
+    Type::Fighting,
+    85,
+    229,
+    20,
+    MoveEffect::JumpKick,
+);
+pub static GLARE: Move = Move::new("Glare", Type::Normal, 0, 191, 30, MoveEffect::Paralyze);
+pub static DREAM_EATER: Move = Move::new(
+    "Dream Eater",
+    Type::Psychic,
+    100,
+    255,
+    15,
+    MoveEffect::DreamEater,
+);
+pub static POISON_GAS: Move = Move::new("Poison Gas", Type::Poison, 0, 140, 40, MoveEffect::Poison);
+pub static BARRAGE: Move = Move::new("Barrage", Type::Normal, 15, 216, 20, MoveEffect::MultiHit);
+pub static LEECH_LIFE: Move = Move::new("Leech Life", Type::Bug, 20, 255, 15, MoveEffect::Drain);
+pub static LOVELY_KISS: Move =
+    Move::new("Lovely Kiss", Type::Normal, 0, 191, 10, MoveEffect::Sleep);
+pub static SKY_ATTACK: Move = Move::new(
+    "Sky Attack",
+    Type::Flying,
+    140,
+    229,
+    5,
+    MoveEffect::Charge(false),
+);
+pub static TRANSFORM: Move = Move::new("Transform", Type::Normal, 0, 0, 10, MoveEffect::Transform);
+pub static BUBBLE: Move = Move::new(
+    "Bubble",
+    Type::Water,
+    20,
+    255,
+    30,
+    MoveEffect::StatDownChance(Stat::Speed, 85),
+);
+pub static DIZZY_PUNCH: Move =
+    Move::new("Dizzy Punch", Type::Normal, 70, 255, 10, MoveEffect::Normal);
+pub static SPORE: Move = Move::new("Spore", Type::Grass, 0, 255, 15, MoveEffect::Sleep);
+pub static FLASH: Move = Move::new(
+    "Flash",
+    Type::Normal,
+    0,
+    178,
+    20,
+    MoveEffect::StatDown(Stat::Accuracy, 1),
+);
+pub static PSYWAVE: Move = Move::new("Psywave", Type::Psychic, 0, 204, 15, MoveEffect::Psywave);
+pub static SPLASH: Move = Move::new("Splash", Type::Normal, 0, 0, 40, MoveEffect::NoEffect);
+pub static ACID_ARMOR: Move = Move::new(
+    "Acid Armor",
+    Type::Poison,
+    0,
+    0,
+    40,
+    MoveEffect::StatUp(Stat::Defense, 2),
+);
+pub static CRABHAMMER: Move =
+    Move::new("Crabhammer", Type::Water, 90, 216, 10, MoveEffect::HighCrit);
+pub static EXPLOSION: Move = Move::new("Explosion", Type::Normal, 170, 255, 5, MoveEffect::SelfKO);
+pub static FURY_SWIPES: Move = Move::new(
+    "Fury Swipes",
+    Type::Normal,
+    18,
+    204,
+    15,
+    MoveEffect::MultiHit,
+);
+pub static BONEMERANG: Move = Move::new(
+    "Bonemerang",
+    Type::Ground,
+    50,
+    229,
+    10,
+    MoveEffect::DoubleHit,
+);
+pub static REST: Move = Move::new("Rest", Type::Psychic, 0, 0, 10, MoveEffect::Rest);
+pub static ROCK_SLIDE: Move = Move::new("Rock Slide", Type::Rock, 75, 229, 10, MoveEffect::Normal);
+pub static HYPER_FANG: Move = Move::new(
+    "Hyper Fang",
+    Type::Normal,
+    80,
+    229,
+    15,
+    MoveEffect::FlinchChance(26),
+);
+pub static SHARPEN: Move = Move::new(
+    "Sharpen",
+    Type::Normal,
+    0,
+    0,
+    30,
+    MoveEffect::StatUp(Stat::Attack, 1),
+);
+pub static CONVERSION: Move =
+    Move::new("Conversion", Type::Normal, 0, 0, 30, MoveEffect::Conversion);
+pub static TRI_ATTACK: Move =
+    Move::new("Tri Attack", Type::Normal, 80, 255, 10, MoveEffect::Normal);
+pub static SUPER_FANG: Move = Move::new(
+    "Super Fang",
+    Type::Normal,
+    0,
+    229,
+    10,
+    MoveEffect::SuperFang,
+);
+pub static SLASH: Move = Move::new("Slash", Type::Normal, 70, 255, 20, MoveEffect::HighCrit);
+pub static SUBSTITUTE: Move =
+    Move::new("Substitute", Type::Normal, 0, 0, 10, MoveEffect::Substitute);
+pub static STRUGGLE: Move = Move::new("Struggle", Type::Normal, 50, 255, 10, recoil(2));
+
+/// All moves, in index order.
+pub static MOVES: [&Move; 165] = [
+    &POUND,
+    &KARATE_CHOP,
+    &DOUBLE_SLAP,
+    &COMET_PUNCH,
+    &MEGA_PUNCH,
+    &PAY_DAY,
+    &FIRE_PUNCH,
+    &ICE_PUNCH,
+    &THUNDER_PUNCH,
+    &SCRATCH,
+    &VICE_GRIP,
+    &GUILLOTINE,
+    &RAZOR_WIND,
+    &SWORDS_DANCE,
+    &CUT,
+    &GUST,
+    &WING_ATTACK,
+    &WHIRLWIND,
+    &FLY,
+    &BIND,
+    &SLAM,
+    &VINE_WHIP,
+    &STOMP,
+    &DOUBLE_KICK,
+    &MEGA_KICK,
+    &JUMP_KICK,
+    &ROLLING_KICK,
+    &SAND_ATTACK,
+    &HEADBUTT,
+    &HORN_ATTACK,
+    &FURY_ATTACK,
+    &HORN_DRILL,
+    &TACKLE,
+    &BODY_SLAM,
+    &WRAP,
+    &TAKE_DOWN,
+    &THRASH,
+    &DOUBLE_EDGE,
+    &TAIL_WHIP,
+    &POISON_STING,
+    &TWINEEDLE,
+    &PIN_MISSILE,
+    &LEER,
+    &BITE,
+    &GROWL,
+    &ROAR,
+    &SING,
+    &SUPERSONIC,
+    &SONIC_BOOM,
+    &DISABLE,
+    &ACID,
+    &EMBER,
+    &FLAMETHROWER,
+    &MIST,
+    &WATER_GUN,
+    &HYDRO_PUMP,
+    &SURF,
+    &ICE_BEAM,
+    &BLIZZARD,
+    &PSYBEAM,
+    &BUBBLE_BEAM,
+    &AURORA_BEAM,
+    &HYPER_BEAM,
+    &PECK,
+    &DRILL_PECK,
+    &SUBMISSION,
+    &LOW_KICK,
+    &COUNTER,
+    &SEISMIC_TOSS,
+    &STRENGTH,
+    &ABSORB,
+    &MEGA_DRAIN,
+    &LEECH_SEED,
+    &GROWTH,
+    &RAZOR_LEAF,
+    &SOLAR_BEAM,
+    &POISON_POWDER,
+    &STUN_SPORE,
+    &SLEEP_POWDER,
+    &PETAL_DANCE,
+    &STRING_SHOT,
+    &DRAGON_RAGE,
+    &FIRE_SPIN,
+    &THUNDER_SHOCK,
+    &THUNDERBOLT,
+    &THUNDER_WAVE,
+    &THUNDER,
+    &ROCK_THROW,
+    &EARTHQUAKE,
+    &FISSURE,
+    &DIG,
+    &TOXIC,
+    &CONFUSION,
+    &PSYCHIC,
+    &HYPNOSIS,
+    &MEDITATE,
+    &AGILITY,
+    &QUICK_ATTACK,
+    &RAGE,
+    &TELEPORT,
+    &NIGHT_SHADE,
+    &MIMIC,
+    &SCREECH,
+    &DOUBLE_TEAM,
+    &RECOVER,
+    &HARDEN,
+    &MINIMIZE,
+    &SMOKESCREEN,
+    &CONFUSE_RAY,
+    &WITHDRAW,
+    &DEFENSE_CURL,
+    &BARRIER,
+    &LIGHT_SCREEN,
+    &HAZE,
+    &REFLECT,
+    &FOCUS_ENERGY,
+    &BIDE,
+    &METRONOME,
+    &MIRROR_MOVE,
+    &SELF_DESTRUCT,
+    &EGG_BOMB,
+    &LICK,
+    &SMOG,
+    &SLUDGE,
+    &BONE_CLUB,
+    &FIRE_BLAST,
+    &WATERFALL,
+    &CLAMP,
+    &SWIFT,
+    &SKULL_BASH,
+    &SPIKE_CANNON,
+    &CONSTRICT,
+    &AMNESIA,
+    &KINESIS,
+    &SOFT_BOILED,
+    &HIGH_JUMP_KICK,
+    &GLARE,
+    &DREAM_EATER,
+    &POISON_GAS,
+    &BARRAGE,
+    &LEECH_LIFE,
+    &LOVELY_KISS,
+    &SKY_ATTACK,
+    &TRANSFORM,
+    &BUBBLE,
+    &DIZZY_PUNCH,
+    &SPORE,
+    &FLASH,
+    &PSYWAVE,
+    &SPLASH,
+    &ACID_ARMOR,
+    &CRABHAMMER,
+    &EXPLOSION,
+    &FURY_SWIPES,
+    &BONEMERANG,
+    &REST,
+    &ROCK_SLIDE,
+    &HYPER_FANG,
+    &SHARPEN,
+    &CONVERSION,
+    &TRI_ATTACK,
+    &SUPER_FANG,
+    &SLASH,
+    &SUBSTITUTE,
+    &STRUGGLE,
+];
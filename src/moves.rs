@@ -1,14 +1,13 @@
 use std::num::NonZeroU8;
 
-use num::rational::Ratio;
-use rand::Rng;
-
-use crate::battle::BattleState;
+use crate::battle::{BattleState, Condition, Ruleset};
+use crate::chance::Chance;
+use crate::movedex;
 use crate::stats::{Modifier, Stat};
-use crate::types::Type;
-use crate::pokemon::Pokemon;
+use crate::types::{Effectiveness, Type};
+use num::rational::Ratio;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum MoveEffect {
     /// Normal, damaging move. The u8 is its power.
     Normal,
@@ -18,97 +17,341 @@ pub enum MoveEffect {
     SelfKO,
     /// High critical hit ratio
     HighCrit,
+    /// Heals the user by half the damage dealt.
+    Drain,
+    /// Like drain, but fails unless the target is asleep.
+    DreamEater,
+    /// Recover or soft-boiled: heal half of the maximum HP.
+    Recover,
+    /// Fully heal, but fall asleep for two turns.
+    Rest,
+    /// Seed the target, draining its HP every turn.
+    LeechSeed,
+    /// Badly poison the target.
+    Toxic,
+    /// Confuse the target.
+    Confuse,
+    /// Damaging move with a chance out of 256 to confuse the target.
+    ConfuseChance(u8),
+    /// Damaging move with a chance out of 256 to make the target flinch.
+    FlinchChance(u8),
+    /// Hyper beam: the user needs to recharge after hitting.
+    HyperBeam,
+    /// Create a substitute at the cost of a quarter of the maximum HP.
+    Substitute,
+    /// Deal double the last damage dealt, if it came from a normal or fighting move.
+    Counter,
+    /// Store energy for 2-3 turns, then deal back double the damage taken.
+    Bide,
+    /// Thrash or petal dance: attack for 3-4 turns, then become confused.
+    Thrash,
+    /// Keep attacking, raising attack whenever hit.
+    Rage,
+    /// Raise a stat of the user by the given number of stages.
+    StatUp(Stat, i8),
+    /// Lower a stat of the target by the given number of stages.
+    StatDown(Stat, i8),
+    /// Damaging move with a chance out of 256 to lower a stat of the target by one stage.
+    StatDownChance(Stat, u8),
+    /// Put the target to sleep.
+    Sleep,
+    /// Paralyze the target.
+    Paralyze,
+    /// Poison the target.
+    Poison,
+    /// Damaging move with a chance out of 256 to burn the target.
+    BurnChance(u8),
+    /// Damaging move with a chance out of 256 to freeze the target.
+    FreezeChance(u8),
+    /// Damaging move with a chance out of 256 to paralyze the target.
+    ParalyzeChance(u8),
+    /// Damaging move with a chance out of 256 to poison the target.
+    PoisonChance(u8),
+    /// Always deals the given amount of damage.
+    FixedDamage(u8),
+    /// Seismic toss or night shade: deals damage equal to the level of the user.
+    LevelDamage,
+    /// Deals a random amount of damage, up to one and a half times the level of the user.
+    Psywave,
+    /// Halves the HP of the target.
+    SuperFang,
+    /// Hits 2-5 times.
+    MultiHit,
+    /// Hits twice.
+    DoubleHit,
+    /// Hits twice, with a chance to poison the target.
+    Twineedle,
+    /// Knocks out the target in one hit, if the user is at least as fast.
+    OneHitKO,
+    /// Charges for a turn before attacking. The bool indicates whether the user is
+    /// invulnerable while charging, like with fly and dig.
+    Charge(bool),
+    /// The user takes crash damage if the move misses.
+    JumpKick,
+    /// Always moves first.
+    Priority,
+    /// Uses a random move.
+    Metronome,
+    /// Uses the last move used by the target.
+    MirrorMove,
+    /// Transform into the target.
+    Transform,
+    /// Replace mimic with a move of the target.
+    Mimic,
+    /// Disable a random move of the target for 1-8 turns.
+    Disable,
+    /// Change the types of the user to those of the target.
+    Conversion,
+    /// Reset stat stages and most volatile conditions on both sides.
+    Haze,
+    /// Double the defense of the user against physical moves.
+    Reflect,
+    /// Double the special of the user against special moves.
+    LightScreen,
+    /// Protect the user against stat drops.
+    Mist,
+    /// Focus energy, supposedly raising the critical hit ratio.
+    FocusEnergy,
+    /// Wrap, bind, clamp and fire spin: attack for 2-5 turns while the target can't move.
+    Trap,
+    /// Does nothing at all.
+    NoEffect,
+}
+
+/// Compute the damage before any modifiers, such as STAB and type effectiveness.
+fn base_damage(power: u32, mut attack: u16, mut defense: u16, level: u16) -> u32 {
+    // Simultaneous reduction
+    if attack > 255 || defense > 255 {
+        attack /= 4;
+        attack &= 0xff;
+        defense /= 4;
+        defense &= 0xff;
+    }
+
+    let mut damage = power * u32::from(attack).max(1);
+    damage *= 2 * u32::from(level / 5 + 2);
+    damage /= u32::from(defense).max(1);
+    2 + 997.min(damage / 50)
+}
+
+/// Apply accuracy and evasion modifiers to an accuracy on the 0..255 scale.
+pub fn modify_accuracy(accuracy: u8, user: Modifier, target: Modifier) -> u8 {
+    let acc = Ratio::from_integer(u16::from(accuracy));
+    let acc = acc * user.get_ratio();
+    let acc = acc.trunc() / target.get_ratio();
+
+    acc.to_integer().min(255) as u8
+}
+
+/// Compute the damage a confused pokemon deals to itself.
+///
+/// This is a typeless, 40 power physical attack from the pokemon onto itself. It is not
+/// affected by STAB, critical hits or the random factor.
+pub fn confusion_damage(user: &BattleState) -> u16 {
+    let level = u16::from(user.pokemon().level);
+    base_damage(40, user[Stat::Attack], user[Stat::Defense], level) as u16
 }
 
-const STRUGGLE: Move = Move {
-    power: unsafe { Some(NonZeroU8::new_unchecked(50)) },
-    accuracy: unsafe { Some(NonZeroU8::new_unchecked(255)) },
-    effect: unsafe { MoveEffect::Recoil(NonZeroU8::new_unchecked(2)) },
-    move_type: Type::Normal,
-};
+/// Pick a number from 2 to 5, where 2 and 3 have a probability of 3/8, 4 and 5 of 1/8.
+fn two_to_five(rand: &mut impl Chance) -> u8 {
+    match rand.byte() & 7 {
+        0..=2 => 2,
+        3..=5 => 3,
+        6 => 4,
+        _ => 5,
+    }
+}
 
 /// A move a pokemon could use.
+#[derive(Debug, Eq, PartialEq, Hash)]
 pub struct Move {
+    name: &'static str,
     power: Option<NonZeroU8>,
     /// Accuracy for the move on a 0..255 scale. None means move always hits.
     accuracy: Option<NonZeroU8>,
+    /// Base PP, without any PP ups.
+    pp: u8,
     effect: MoveEffect,
     move_type: Type,
 }
 
 impl Move {
+    /// Create a new move.
+    ///
+    /// A power of zero means the move does no direct damage, an accuracy of zero means the move
+    /// always hits.
+    pub const fn new(
+        name: &'static str,
+        move_type: Type,
+        power: u8,
+        accuracy: u8,
+        pp: u8,
+        effect: MoveEffect,
+    ) -> Self {
+        Move {
+            name,
+            power: NonZeroU8::new(power),
+            accuracy: NonZeroU8::new(accuracy),
+            pp,
+            effect,
+            move_type,
+        }
+    }
+
     /// Fallback move for when no other move is available.
     ///
     /// This move returns a reference to some internal constant for the move struggle.
     pub fn fallback() -> &'static Move {
-        &STRUGGLE
+        &movedex::STRUGGLE
     }
 
-    pub fn hits(&self, rand: &mut impl Rng, accuracy: Modifier, evasion: Modifier) -> bool {
-        if let Some(acc) = self.accuracy {
-            let acc = Ratio::from_integer(u16::from(acc.get()));
-            let acc = acc * accuracy.get_ratio();
-            let acc = acc.trunc() / evasion.get_ratio();
-            let r: u8 = rand.gen();
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn effect(&self) -> MoveEffect {
+        self.effect
+    }
 
-            acc.to_integer() > u16::from(r)
+    /// Get the base PP of this move.
+    pub fn pp(&self) -> u8 {
+        self.pp
+    }
+
+    pub fn move_type(&self) -> Type {
+        self.move_type
+    }
+
+    /// Get the priority of this move. Moves with higher priority go first.
+    pub fn priority(&self) -> i8 {
+        match self.effect {
+            MoveEffect::Priority => 1,
+            MoveEffect::Counter => -1,
+            _ => 0,
+        }
+    }
+
+    /// Check whether counter can return damage dealt by this move.
+    ///
+    /// Only damaging normal and fighting type moves can be countered, except for counter itself.
+    pub fn can_be_countered(&self) -> bool {
+        self.power.is_some()
+            && self.effect != MoveEffect::Counter
+            && matches!(self.move_type, Type::Normal | Type::Fighting)
+    }
+
+    /// Check whether this move fails outright against the given target.
+    ///
+    /// This is checked before accuracy. Currently only dream eater can fail this way, when
+    /// the target is not asleep.
+    pub fn fails_against(&self, target: &BattleState) -> bool {
+        match self.effect {
+            MoveEffect::DreamEater => !matches!(target.condition(), Some(Condition::Asleep(_))),
+            _ => false,
+        }
+    }
+
+    pub fn hits(&self, rand: &mut impl Chance, accuracy: Modifier, evasion: Modifier) -> bool {
+        if let Some(acc) = self.accuracy {
+            rand.chance(modify_accuracy(acc.get(), accuracy, evasion))
         } else {
             true
         }
     }
 
+    /// Get the base power of this move, if it uses the damage formula.
+    pub fn power(&self) -> Option<u8> {
+        self.power.map(NonZeroU8::get)
+    }
+
+    /// Get the base accuracy of this move, if it can miss.
+    pub fn accuracy(&self) -> Option<u8> {
+        self.accuracy.map(NonZeroU8::get)
+    }
+
     /// Compute the damage for when the attacker hits the defender with this particular move.
+    ///
+    /// Critical hits double the level of the attacker in the damage formula, and ignore stat
+    /// stages and status drops.
     pub fn damage(
         &self,
-        rand: &mut impl Rng,
+        rand: &mut impl Chance,
         attacker: &BattleState,
         defender: &BattleState,
+        critical: bool,
+    ) -> u16 {
+        // gen_range is open ended at the high end
+        let roll: u32 = rand.range(217, 256);
+        self.damage_with_roll(attacker, defender, critical, roll as u8)
+    }
+
+    /// Compute the damage for a specific random roll, from 217 up to and including 255.
+    pub fn damage_with_roll(
+        &self,
+        attacker: &BattleState,
+        defender: &BattleState,
+        critical: bool,
+        roll: u8,
     ) -> u16 {
         if self.power.is_none() {
             return 0;
         }
 
         let power = u32::from(self.power.unwrap().get());
-        let (mut attack, mut defense) = if self.move_type.is_physical() {
-            (attacker[Stat::Attack], defender[Stat::Defense])
+        let (attack, defense) = if self.move_type.is_physical() {
+            (Stat::Attack, Stat::Defense)
+        } else {
+            (Stat::Special, Stat::Special)
+        };
+        // Critical hits use the stats without stat stages or status drops.
+        let (attack, mut defense) = if critical {
+            (attacker.raw_stat(attack), defender.raw_stat(defense))
+        } else {
+            (attacker[attack], defender[defense])
+        };
+
+        // Reflect and light screen double the defending stat, but not on critical hits.
+        let screened = if self.move_type.is_physical() {
+            defender.has_reflect()
         } else {
-            (attacker[Stat::Special], defender[Stat::Special])
+            defender.has_light_screen()
         };
+        if screened && !critical {
+            defense *= 2;
+        }
 
         // TODO: badge bonus
         if self.effect == MoveEffect::SelfKO {
             defense /= 2;
         }
 
-        // Simultaneous reduction
-        if attack > 255 || defense > 255 {
-            attack /= 4;
-            attack &= 0xff;
-            defense /= 4;
-            defense &= 0xff;
+        let mut level = u16::from(attacker.pokemon().level);
+        if critical {
+            level *= 2;
         }
 
-        let mut damage = power * u32::from(attack).max(1);
-        // TODO: critical hits
-        damage *= 2 * u32::from(attacker.pokemon().level / 5 + 2);
-        // TODO: light screen & reflect
-        damage /= u32::from(defense).max(1);
-        damage = 2 + 997.min(damage / 50);
+        let mut damage = base_damage(power, attack, defense, level);
 
         // Same-Type Attack bonus
-        if attacker.pokemon().has_type(self.move_type) {
+        if attacker.has_type(self.move_type) {
             damage = damage * 3 / 2;
         }
 
-        damage = self.apply_type_effectiveness(defender.pokemon(), damage);
+        damage = self.apply_type_effectiveness(defender, damage);
 
-        // gen_range is open ended at the high end
-        let r: u32 = rand.gen_range(217, 256);
+        (damage * u32::from(roll) / 255) as u16
+    }
 
-        (damage * r / 255) as u16
+    /// Check whether the defender is immune to this move based on its type.
+    pub fn is_immune(&self, defender: &BattleState) -> bool {
+        defender
+            .get_types()
+            .iter()
+            .filter_map(|&x| x)
+            .any(|t| self.move_type.effectiveness(t) == Effectiveness::Immune)
     }
 
-    fn apply_type_effectiveness(&self, defender: &Pokemon, damage: u32) -> u32 {
+    fn apply_type_effectiveness(&self, defender: &BattleState, damage: u32) -> u32 {
         let mut damage = Ratio::from_integer(damage);
         for defender_type in defender.get_types().iter().filter_map(|&x| x) {
             damage *= self.move_type.effectiveness(defender_type).get_modifier();
@@ -119,27 +362,206 @@ impl Move {
 
     /// Check if this move is a critical hit.
     ///
-    /// This function implement the gen one (RBY, not stadium) algorithm for determining critical
-    /// hits. As such, it is inherently random.
-    pub fn is_critical(&self, rand: &mut impl Rng, attacker: &BattleState) -> bool {
-        // Not implemented: dire hit/focus energy since they are bugged anyway.
-        let base_speed = attacker.pokemon().base_stats[usize::from(Stat::Speed)];
+    /// This function implements the gen one algorithm for determining critical hits. As such, it
+    /// is inherently random. On the cartridge, focus energy and dire hit divide the threshold by
+    /// four instead of multiplying it, which is fixed in stadium.
+    pub fn is_critical(
+        &self,
+        rand: &mut impl Chance,
+        attacker: &BattleState,
+        ruleset: Ruleset,
+    ) -> bool {
+        rand.chance(self.critical_threshold(attacker, ruleset))
+    }
+
+    /// Get the threshold for critical hits, out of 256.
+    pub fn critical_threshold(&self, attacker: &BattleState, ruleset: Ruleset) -> u8 {
+        let base_speed = attacker.species().base_stats[usize::from(Stat::Speed)];
         let mut t = base_speed / 2;
+        if attacker.has_focus_energy() {
+            match ruleset {
+                Ruleset::Cartridge => t /= 4,
+                Ruleset::Stadium => t *= 4,
+            }
+        }
         if self.effect == MoveEffect::HighCrit {
             t *= 4;
         }
-        let t = t.min(255) as u8;
-        let r: u8 = rand.gen();
-
-        r < t
+        t.min(255) as u8
     }
 
     pub fn get_recoil(&self, damage: u16) -> Option<u16> {
         match self.effect {
             MoveEffect::Recoil(c) => Some((damage / u16::from(c.get())).max(1)),
-            _ => None
+            _ => None,
+        }
+    }
+
+    /// Check whether this move confuses the target after hitting it.
+    pub fn confuses(&self, rand: &mut impl Chance) -> bool {
+        match self.effect {
+            MoveEffect::Confuse => true,
+            MoveEffect::ConfuseChance(chance) => rand.chance(chance),
+            _ => false,
+        }
+    }
+
+    /// Check whether this move makes the target flinch after hitting it.
+    pub fn flinches(&self, rand: &mut impl Chance) -> bool {
+        match self.effect {
+            MoveEffect::FlinchChance(chance) => rand.chance(chance),
+            _ => false,
+        }
+    }
+
+    /// Get the non-volatile status this move inflicts on the target after hitting it, if any.
+    pub fn secondary_condition(&self, rand: &mut impl Chance) -> Option<Condition> {
+        let (condition, chance) = match self.effect {
+            MoveEffect::BurnChance(chance) => (Condition::Burned, chance),
+            MoveEffect::FreezeChance(chance) => (Condition::Frozen, chance),
+            MoveEffect::ParalyzeChance(chance) => (Condition::Paralyzed, chance),
+            MoveEffect::PoisonChance(chance) => (Condition::Poisoned(false), chance),
+            MoveEffect::Twineedle => (Condition::Poisoned(false), 52),
+            _ => return None,
+        };
+
+        if rand.chance(chance) {
+            Some(condition)
+        } else {
+            None
+        }
+    }
+
+    /// Get the stat of the target this move lowers after hitting it, if any.
+    pub fn secondary_stat_drop(&self, rand: &mut impl Chance) -> Option<Stat> {
+        match self.effect {
+            MoveEffect::StatDownChance(stat, chance) if rand.chance(chance) => Some(stat),
+            _ => None,
+        }
+    }
+
+    /// Determine how many times this move hits.
+    ///
+    /// Multi-hit moves hit 2 or 3 times with a probability of 3/8 each, and 4 or 5 times with
+    /// a probability of 1/8 each.
+    pub fn hit_count(&self, rand: &mut impl Chance) -> u8 {
+        match self.effect {
+            MoveEffect::MultiHit => two_to_five(rand),
+            MoveEffect::DoubleHit | MoveEffect::Twineedle => 2,
+            _ => 1,
         }
     }
+
+    /// Determine for how many turns a trapping move attacks, including the first.
+    ///
+    /// This uses the same distribution as multi-hit moves. Returns None for other moves.
+    pub fn trap_turns(&self, rand: &mut impl Chance) -> Option<u8> {
+        match self.effect {
+            MoveEffect::Trap => Some(two_to_five(rand)),
+            _ => None,
+        }
+    }
+
+    /// Get the damage for moves that ignore the damage formula.
+    ///
+    /// These moves also ignore type effectiveness. Returns None for all other moves.
+    pub fn fixed_damage(
+        &self,
+        rand: &mut impl Chance,
+        attacker: &BattleState,
+        defender: &BattleState,
+    ) -> Option<u16> {
+        match self.effect {
+            MoveEffect::Psywave => {
+                let level = u16::from(attacker.pokemon().level);
+                Some(rand.range(1, (level * 3 / 2).max(2)))
+            }
+            _ => self.deterministic_damage(attacker, defender),
+        }
+    }
+
+    /// Get the damage for the fixed damage moves that don't use any randomness: Seismic Toss,
+    /// Night Shade, Dragon Rage, Sonic Boom and Super Fang. Returns None for all other moves.
+    pub fn deterministic_damage(
+        &self,
+        attacker: &BattleState,
+        defender: &BattleState,
+    ) -> Option<u16> {
+        match self.effect {
+            MoveEffect::FixedDamage(damage) => Some(u16::from(damage)),
+            MoveEffect::LevelDamage => Some(u16::from(attacker.pokemon().level)),
+            MoveEffect::SuperFang => Some((defender.hit_points() / 2).max(1)),
+            _ => None,
+        }
+    }
+
+    /// Get the amount of HP the user regains after dealing the given damage.
+    pub fn get_drain(&self, damage: u16) -> Option<u16> {
+        match self.effect {
+            MoveEffect::Drain | MoveEffect::DreamEater => Some((damage / 2).max(1)),
+            _ => None,
+        }
+    }
+}
+
+/// A move in the moveset of a pokemon, along with its PP.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MoveSlot {
+    known: &'static Move,
+    /// PP in the lower six bits and PP ups in the upper two, like the cartridge stores them.
+    pp: u8,
+}
+
+impl MoveSlot {
+    /// Create a move slot with full PP and no PP ups.
+    pub const fn new(known: &'static Move) -> Self {
+        MoveSlot::with_pp_ups(known, 0)
+    }
+
+    /// Create a move slot with full PP and the given number of PP ups, at most 3.
+    pub const fn with_pp_ups(known: &'static Move, pp_ups: u8) -> Self {
+        let pp_ups = if pp_ups > 3 { 3 } else { pp_ups };
+        let bonus = if known.pp / 5 > 7 { 7 } else { known.pp / 5 };
+        MoveSlot {
+            known,
+            pp: (pp_ups << 6) | (known.pp + bonus * pp_ups),
+        }
+    }
+
+    pub fn known(&self) -> &'static Move {
+        self.known
+    }
+
+    pub fn pp(&self) -> u8 {
+        self.pp & 0x3f
+    }
+
+    pub fn pp_ups(&self) -> u8 {
+        self.pp >> 6
+    }
+
+    /// Get the PP of a full slot, including the PP ups.
+    pub fn max_pp(&self) -> u8 {
+        MoveSlot::with_pp_ups(self.known, self.pp_ups()).pp()
+    }
+
+    /// Set the current PP, keeping the PP ups.
+    pub fn set_pp(&mut self, pp: u8) {
+        self.pp = (self.pp & 0xc0) | (pp & 0x3f);
+    }
+
+    /// Use up a single PP.
+    ///
+    /// Like on the cartridge, this doesn't check for zero PP. Using a move with no PP left
+    /// underflows into the PP up bits, leaving 63 PP and a different number of PP ups.
+    pub fn use_pp(&mut self) {
+        self.pp = self.pp.wrapping_sub(1);
+    }
+
+    /// Replace the move in this slot, keeping the PP. This is what mimic does.
+    pub fn replace(&mut self, known: &'static Move) {
+        self.known = known;
+    }
 }
 
 #[cfg(test)]
@@ -155,12 +577,9 @@ mod tests {
         let m = Move::fallback();
         let mut rng = StepRng::new(254, 1);
         // First one struggle should hit.
-        assert_eq!(true, m.hits(&mut rng, Modifier::default(), Modifier::default()));
+        assert!(m.hits(&mut rng, Modifier::default(), Modifier::default()));
         // Second should be affected by the 1/256 glitch.
-        assert_eq!(
-            false,
-            m.hits(&mut rng, Modifier::default(), Modifier::default())
-        );
+        assert!(!m.hits(&mut rng, Modifier::default(), Modifier::default()));
 
         // Test how many hits we get for all possible random numbers
         let hits = (0..=0xff)
@@ -179,12 +598,121 @@ mod tests {
             base_stats: [100; 5],
             ..Default::default()
         };
-        let state = BattleState::new(&mew);
+        let mut state = BattleState::new(&mew);
 
-        let hits = (0..=0xff)
-            .filter(|_| m.is_critical(&mut rng, &state))
-            .count();
+        let mut count = |state: &BattleState, ruleset| {
+            (0..=0xff)
+                .filter(|_| m.is_critical(&mut rng, state, ruleset))
+                .count()
+        };
         // Mew has a probability of 50/256 to hit a critical, so…
-        assert_eq!(50, hits);
+        assert_eq!(50, count(&state, Ruleset::Cartridge));
+
+        // Focus energy quarters the chance on the cartridge, but quadruples it in stadium.
+        state.use_focus_energy();
+        assert_eq!(12, count(&state, Ruleset::Cartridge));
+        assert_eq!(200, count(&state, Ruleset::Stadium));
+    }
+
+    #[test]
+    fn test_drain() {
+        let absorb = &crate::movedex::ABSORB;
+        assert_eq!(Some(10), absorb.get_drain(21));
+        // Draining always heals at least one hit point.
+        assert_eq!(Some(1), absorb.get_drain(1));
+        assert_eq!(None, Move::fallback().get_drain(20));
+    }
+
+    #[test]
+    fn test_dream_eater_requires_sleep() {
+        let dream_eater = &crate::movedex::DREAM_EATER;
+        let pokemon = Pokemon::default();
+        let mut target = BattleState::new(&pokemon);
+
+        assert!(dream_eater.fails_against(&target));
+        target.set_condition(Some(Condition::Asleep(3)));
+        assert!(!dream_eater.fails_against(&target));
+    }
+
+    #[test]
+    fn test_confusion_damage() {
+        let rattata = Pokemon::default();
+        let state = BattleState::new(&rattata);
+
+        // 40 power, 210 attack, 168 defense at level 100.
+        assert_eq!(46, confusion_damage(&state));
+    }
+
+    #[test]
+    fn test_reflect() {
+        let pokemon = Pokemon::default();
+        let attacker = BattleState::new(&pokemon);
+        let mut defender = BattleState::new(&pokemon);
+        let mut rng = StepRng::new(60, 0);
+
+        let tackle = &movedex::TACKLE;
+        let unscreened = tackle.damage(&mut rng, &attacker, &defender, false);
+        defender.use_reflect();
+        let screened = tackle.damage(&mut rng, &attacker, &defender, false);
+        assert!(screened < unscreened);
+
+        // Critical hits ignore reflect.
+        let critical = tackle.damage(&mut rng, &attacker, &defender, true);
+        assert!(critical > unscreened);
+        // Light screen does nothing against physical moves.
+        defender.haze();
+        defender.use_light_screen();
+        assert_eq!(
+            unscreened,
+            tackle.damage(&mut rng, &attacker, &defender, false)
+        );
+    }
+
+    #[test]
+    fn test_critical_ignores_modifiers() {
+        let pokemon = Pokemon::default();
+        let attacker = BattleState::new(&pokemon);
+        let defender = BattleState::new(&pokemon);
+        let tackle = &movedex::TACKLE;
+        let critical = tackle.damage_with_roll(&attacker, &defender, true, 255);
+        let normal = tackle.damage_with_roll(&attacker, &defender, false, 255);
+
+        let mut boosted = BattleState::new(&pokemon);
+        boosted.modify_stat(Stat::Attack, 2);
+        assert!(tackle.damage_with_roll(&boosted, &defender, false, 255) > normal);
+        assert_eq!(
+            critical,
+            tackle.damage_with_roll(&boosted, &defender, true, 255)
+        );
+
+        let mut burned = BattleState::new(&pokemon);
+        burned.inflict(Condition::Burned);
+        assert!(tackle.damage_with_roll(&burned, &defender, false, 255) < normal);
+        assert_eq!(
+            critical,
+            tackle.damage_with_roll(&burned, &defender, true, 255)
+        );
+
+        let mut hardened = BattleState::new(&pokemon);
+        hardened.modify_stat(Stat::Defense, 2);
+        assert_eq!(
+            critical,
+            tackle.damage_with_roll(&attacker, &hardened, true, 255)
+        );
+    }
+
+    #[test]
+    fn test_pp_underflow() {
+        let mut slot = MoveSlot::with_pp_ups(&movedex::GROWL, 3);
+        // Growl would get 40 + 3 * 8 PP, but the bonus per PP up is capped at 7.
+        assert_eq!(61, slot.pp());
+
+        slot.set_pp(1);
+        assert_eq!(61, slot.max_pp());
+        slot.use_pp();
+        assert_eq!((0, 3), (slot.pp(), slot.pp_ups()));
+
+        slot.use_pp();
+        assert_eq!((63, 2), (slot.pp(), slot.pp_ups()));
     }
 }
@@ -0,0 +1,104 @@
+//! Players that choose the actions of a side in a battle.
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::battle::{Action, Battle};
+use crate::calc::damage_distribution;
+
+/// Something that chooses actions for a side in a battle.
+pub trait Player {
+    /// Choose an action for the given side.
+    ///
+    /// The action should be one of `Battle::available_actions`. This is also used to choose
+    /// a replacement after the active pokemon fainted, when only switches are available.
+    fn choose(&mut self, battle: &Battle, side: usize) -> Action;
+}
+
+/// Player that picks any of the available actions at random.
+pub struct RandomPlayer<R> {
+    rand: R,
+}
+
+impl<R: Rng> RandomPlayer<R> {
+    pub fn new(rand: R) -> Self {
+        RandomPlayer { rand }
+    }
+}
+
+impl<R: Rng> Player for RandomPlayer<R> {
+    fn choose(&mut self, battle: &Battle, side: usize) -> Action {
+        *battle
+            .available_actions(side)
+            .choose(&mut self.rand)
+            .expect("No actions available")
+    }
+}
+
+/// Player that uses the move with the highest expected damage, and never switches voluntarily.
+///
+/// When it has to send out a new pokemon, it picks the first one that can still fight.
+#[derive(Default)]
+pub struct GreedyPlayer;
+
+impl Player for GreedyPlayer {
+    fn choose(&mut self, battle: &Battle, side: usize) -> Action {
+        let actions = battle.available_actions(side);
+        let user = battle.side(side);
+        let target = battle.side(1 - side);
+
+        let expected = |action: &Action| match *action {
+            Action::UseMove(used) => {
+                let expected = damage_distribution(used, user, target, battle.ruleset()).expected();
+                Some(expected)
+            }
+            Action::Switch(_) => None,
+        };
+
+        // Ties go to the earliest action.
+        let mut best = *actions.first().expect("No actions available");
+        let mut best_damage = expected(&best);
+        for action in &actions[1..] {
+            let damage = expected(action);
+            if damage > best_damage {
+                best = *action;
+                best_damage = damage;
+            }
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::mock::StepRng;
+
+    use crate::battle::Ruleset;
+    use crate::movedex;
+    use crate::moves::MoveSlot;
+    use crate::pokemon::Pokemon;
+
+    use super::*;
+
+    #[test]
+    fn test_greedy_player() {
+        let mut pokemon = Pokemon::default();
+        pokemon.moves[0] = Some(MoveSlot::new(&movedex::GROWL));
+        pokemon.moves[1] = Some(MoveSlot::new(&movedex::TACKLE));
+        pokemon.moves[2] = Some(MoveSlot::new(&movedex::BODY_SLAM));
+        let team = [pokemon, Pokemon::default()];
+        let mut battle = Battle::with_teams(&team, &team, Ruleset::Cartridge);
+
+        let mut player = GreedyPlayer;
+        assert!(matches!(
+            player.choose(&battle, 0),
+            Action::UseMove(used) if used.name() == "Body Slam"
+        ));
+
+        battle.side_mut(0).damage_direct(999);
+        assert!(matches!(player.choose(&battle, 0), Action::Switch(1)));
+
+        let mut player = RandomPlayer::new(StepRng::new(0, 0));
+        assert!(matches!(player.choose(&battle, 0), Action::Switch(1)));
+    }
+}
@@ -0,0 +1,587 @@
+//! Species data for all 151 pokemon of generation one.
+//!
+//! Species are listed in the order of their pokedex number.
+use crate::pokemon::Pokemon;
+use crate::stats::{StatSet, PERFECT_EVS, PERFECT_IVS};
+use crate::types::Type;
+use crate::utils::normalize_name;
+
+/// Base data for a pokemon species.
+#[derive(Debug)]
+pub struct Species {
+    pub name: &'static str,
+    pub base_stats: StatSet,
+    pub types: [Option<Type>; 2],
+}
+
+impl Species {
+    /// Create a pokemon of this species at the given level.
+    ///
+    /// The pokemon has perfect IVs and EVs and no moves, like `Pokemon::default`.
+    pub fn pokemon(&self, level: u8) -> Pokemon {
+        Pokemon {
+            level,
+            base_stats: self.base_stats,
+            evs: PERFECT_EVS,
+            ivs: PERFECT_IVS,
+            types: self.types,
+            moves: [None; 4],
+        }
+    }
+}
+
+const fn species(name: &'static str, base_stats: StatSet, types: [Option<Type>; 2]) -> Species {
+    Species {
+        name,
+        base_stats,
+        types,
+    }
+}
+
+/// Find a species by name.
+///
+/// Case, spaces and punctuation are ignored, and the Nidoran can be written as "Nidoran-F" and
+/// "Nidoran-M".
+pub fn find(name: &str) -> Option<&'static Species> {
+    let name = normalize_name(name);
+    SPECIES.iter().find(|s| normalize_name(s.name) == name)
+}
+
+pub static SPECIES: [Species; 151] = [
+    species(
+        "Bulbasaur",
+        [45, 49, 49, 65, 45],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Ivysaur",
+        [60, 62, 63, 80, 60],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Venusaur",
+        [80, 82, 83, 100, 80],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species("Charmander", [39, 52, 43, 50, 65], [Some(Type::Fire), None]),
+    species("Charmeleon", [58, 64, 58, 65, 80], [Some(Type::Fire), None]),
+    species(
+        "Charizard",
+        [78, 84, 78, 85, 100],
+        [Some(Type::Fire), Some(Type::Flying)],
+    ),
+    species("Squirtle", [44, 48, 65, 50, 43], [Some(Type::Water), None]),
+    species("Wartortle", [59, 63, 80, 65, 58], [Some(Type::Water), None]),
+    species(
+        "Blastoise",
+        [79, 83, 100, 85, 78],
+        [Some(Type::Water), None],
+    ),
+    species("Caterpie", [45, 30, 35, 20, 45], [Some(Type::Bug), None]),
+    species("Metapod", [50, 20, 55, 25, 30], [Some(Type::Bug), None]),
+    species(
+        "Butterfree",
+        [60, 45, 50, 80, 70],
+        [Some(Type::Bug), Some(Type::Flying)],
+    ),
+    species(
+        "Weedle",
+        [40, 35, 30, 20, 50],
+        [Some(Type::Bug), Some(Type::Poison)],
+    ),
+    species(
+        "Kakuna",
+        [45, 25, 50, 25, 35],
+        [Some(Type::Bug), Some(Type::Poison)],
+    ),
+    species(
+        "Beedrill",
+        [65, 80, 40, 45, 75],
+        [Some(Type::Bug), Some(Type::Poison)],
+    ),
+    species(
+        "Pidgey",
+        [40, 45, 40, 35, 56],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species(
+        "Pidgeotto",
+        [63, 60, 55, 50, 71],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species(
+        "Pidgeot",
+        [83, 80, 75, 70, 91],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species("Rattata", [30, 56, 35, 25, 72], [Some(Type::Normal), None]),
+    species("Raticate", [55, 81, 60, 50, 97], [Some(Type::Normal), None]),
+    species(
+        "Spearow",
+        [40, 60, 30, 31, 70],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species(
+        "Fearow",
+        [65, 90, 65, 61, 100],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species("Ekans", [35, 60, 44, 40, 55], [Some(Type::Poison), None]),
+    species("Arbok", [60, 85, 69, 65, 80], [Some(Type::Poison), None]),
+    species(
+        "Pikachu",
+        [35, 55, 30, 50, 90],
+        [Some(Type::Electric), None],
+    ),
+    species(
+        "Raichu",
+        [60, 90, 55, 90, 100],
+        [Some(Type::Electric), None],
+    ),
+    species(
+        "Sandshrew",
+        [50, 75, 85, 30, 40],
+        [Some(Type::Ground), None],
+    ),
+    species(
+        "Sandslash",
+        [75, 100, 110, 55, 65],
+        [Some(Type::Ground), None],
+    ),
+    species("Nidoran♀", [55, 47, 52, 40, 41], [Some(Type::Poison), None]),
+    species("Nidorina", [70, 62, 67, 55, 56], [Some(Type::Poison), None]),
+    species(
+        "Nidoqueen",
+        [90, 82, 87, 75, 76],
+        [Some(Type::Poison), Some(Type::Ground)],
+    ),
+    species("Nidoran♂", [46, 57, 40, 40, 50], [Some(Type::Poison), None]),
+    species("Nidorino", [61, 72, 57, 55, 65], [Some(Type::Poison), None]),
+    species(
+        "Nidoking",
+        [81, 92, 77, 75, 85],
+        [Some(Type::Poison), Some(Type::Ground)],
+    ),
+    species("Clefairy", [70, 45, 48, 60, 35], [Some(Type::Normal), None]),
+    species("Clefable", [95, 70, 73, 85, 60], [Some(Type::Normal), None]),
+    species("Vulpix", [38, 41, 40, 65, 65], [Some(Type::Fire), None]),
+    species(
+        "Ninetales",
+        [73, 76, 75, 100, 100],
+        [Some(Type::Fire), None],
+    ),
+    species(
+        "Jigglypuff",
+        [115, 45, 20, 25, 20],
+        [Some(Type::Normal), None],
+    ),
+    species(
+        "Wigglytuff",
+        [140, 70, 45, 50, 45],
+        [Some(Type::Normal), None],
+    ),
+    species(
+        "Zubat",
+        [40, 45, 35, 40, 55],
+        [Some(Type::Poison), Some(Type::Flying)],
+    ),
+    species(
+        "Golbat",
+        [75, 80, 70, 75, 90],
+        [Some(Type::Poison), Some(Type::Flying)],
+    ),
+    species(
+        "Oddish",
+        [45, 50, 55, 75, 30],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Gloom",
+        [60, 65, 70, 85, 40],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Vileplume",
+        [75, 80, 85, 100, 50],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Paras",
+        [35, 70, 55, 55, 25],
+        [Some(Type::Bug), Some(Type::Grass)],
+    ),
+    species(
+        "Parasect",
+        [60, 95, 80, 80, 30],
+        [Some(Type::Bug), Some(Type::Grass)],
+    ),
+    species(
+        "Venonat",
+        [60, 55, 50, 40, 45],
+        [Some(Type::Bug), Some(Type::Poison)],
+    ),
+    species(
+        "Venomoth",
+        [70, 65, 60, 90, 90],
+        [Some(Type::Bug), Some(Type::Poison)],
+    ),
+    species("Diglett", [10, 55, 25, 45, 95], [Some(Type::Ground), None]),
+    species("Dugtrio", [35, 80, 50, 70, 120], [Some(Type::Ground), None]),
+    species("Meowth", [40, 45, 35, 40, 90], [Some(Type::Normal), None]),
+    species("Persian", [65, 70, 60, 65, 115], [Some(Type::Normal), None]),
+    species("Psyduck", [50, 52, 48, 50, 55], [Some(Type::Water), None]),
+    species("Golduck", [80, 82, 78, 80, 85], [Some(Type::Water), None]),
+    species("Mankey", [40, 80, 35, 35, 70], [Some(Type::Fighting), None]),
+    species(
+        "Primeape",
+        [65, 105, 60, 60, 95],
+        [Some(Type::Fighting), None],
+    ),
+    species("Growlithe", [55, 70, 45, 50, 60], [Some(Type::Fire), None]),
+    species("Arcanine", [90, 110, 80, 80, 95], [Some(Type::Fire), None]),
+    species("Poliwag", [40, 50, 40, 40, 90], [Some(Type::Water), None]),
+    species("Poliwhirl", [65, 65, 65, 50, 90], [Some(Type::Water), None]),
+    species(
+        "Poliwrath",
+        [90, 85, 95, 70, 70],
+        [Some(Type::Water), Some(Type::Fighting)],
+    ),
+    species("Abra", [25, 20, 15, 105, 90], [Some(Type::Psychic), None]),
+    species(
+        "Kadabra",
+        [40, 35, 30, 120, 105],
+        [Some(Type::Psychic), None],
+    ),
+    species(
+        "Alakazam",
+        [55, 50, 45, 135, 120],
+        [Some(Type::Psychic), None],
+    ),
+    species("Machop", [70, 80, 50, 35, 35], [Some(Type::Fighting), None]),
+    species(
+        "Machoke",
+        [80, 100, 70, 50, 45],
+        [Some(Type::Fighting), None],
+    ),
+    species(
+        "Machamp",
+        [90, 130, 80, 65, 55],
+        [Some(Type::Fighting), None],
+    ),
+    species(
+        "Bellsprout",
+        [50, 75, 35, 70, 40],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Weepinbell",
+        [65, 90, 50, 85, 55],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Victreebel",
+        [80, 105, 65, 100, 70],
+        [Some(Type::Grass), Some(Type::Poison)],
+    ),
+    species(
+        "Tentacool",
+        [40, 40, 35, 100, 70],
+        [Some(Type::Water), Some(Type::Poison)],
+    ),
+    species(
+        "Tentacruel",
+        [80, 70, 65, 120, 100],
+        [Some(Type::Water), Some(Type::Poison)],
+    ),
+    species(
+        "Geodude",
+        [40, 80, 100, 30, 20],
+        [Some(Type::Rock), Some(Type::Ground)],
+    ),
+    species(
+        "Graveler",
+        [55, 95, 115, 45, 35],
+        [Some(Type::Rock), Some(Type::Ground)],
+    ),
+    species(
+        "Golem",
+        [80, 110, 130, 55, 45],
+        [Some(Type::Rock), Some(Type::Ground)],
+    ),
+    species("Ponyta", [50, 85, 55, 65, 90], [Some(Type::Fire), None]),
+    species("Rapidash", [65, 100, 70, 80, 105], [Some(Type::Fire), None]),
+    species(
+        "Slowpoke",
+        [90, 65, 65, 40, 15],
+        [Some(Type::Water), Some(Type::Psychic)],
+    ),
+    species(
+        "Slowbro",
+        [95, 75, 110, 80, 30],
+        [Some(Type::Water), Some(Type::Psychic)],
+    ),
+    species(
+        "Magnemite",
+        [25, 35, 70, 95, 45],
+        [Some(Type::Electric), None],
+    ),
+    species(
+        "Magneton",
+        [50, 60, 95, 120, 70],
+        [Some(Type::Electric), None],
+    ),
+    species(
+        "Farfetch'd",
+        [52, 65, 55, 58, 60],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species(
+        "Doduo",
+        [35, 85, 45, 35, 75],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species(
+        "Dodrio",
+        [60, 110, 70, 60, 100],
+        [Some(Type::Normal), Some(Type::Flying)],
+    ),
+    species("Seel", [65, 45, 55, 70, 45], [Some(Type::Water), None]),
+    species(
+        "Dewgong",
+        [90, 70, 80, 95, 70],
+        [Some(Type::Water), Some(Type::Ice)],
+    ),
+    species("Grimer", [80, 80, 50, 40, 25], [Some(Type::Poison), None]),
+    species("Muk", [105, 105, 75, 65, 50], [Some(Type::Poison), None]),
+    species("Shellder", [30, 65, 100, 45, 40], [Some(Type::Water), None]),
+    species(
+        "Cloyster",
+        [50, 95, 180, 85, 70],
+        [Some(Type::Water), Some(Type::Ice)],
+    ),
+    species(
+        "Gastly",
+        [30, 35, 30, 100, 80],
+        [Some(Type::Ghost), Some(Type::Poison)],
+    ),
+    species(
+        "Haunter",
+        [45, 50, 45, 115, 95],
+        [Some(Type::Ghost), Some(Type::Poison)],
+    ),
+    species(
+        "Gengar",
+        [60, 65, 60, 130, 110],
+        [Some(Type::Ghost), Some(Type::Poison)],
+    ),
+    species(
+        "Onix",
+        [35, 45, 160, 30, 70],
+        [Some(Type::Rock), Some(Type::Ground)],
+    ),
+    species("Drowzee", [60, 48, 45, 90, 42], [Some(Type::Psychic), None]),
+    species("Hypno", [85, 73, 70, 115, 67], [Some(Type::Psychic), None]),
+    species("Krabby", [30, 105, 90, 25, 50], [Some(Type::Water), None]),
+    species("Kingler", [55, 130, 115, 50, 75], [Some(Type::Water), None]),
+    species(
+        "Voltorb",
+        [40, 30, 50, 55, 100],
+        [Some(Type::Electric), None],
+    ),
+    species(
+        "Electrode",
+        [60, 50, 70, 80, 140],
+        [Some(Type::Electric), None],
+    ),
+    species(
+        "Exeggcute",
+        [60, 40, 80, 60, 40],
+        [Some(Type::Grass), Some(Type::Psychic)],
+    ),
+    species(
+        "Exeggutor",
+        [95, 95, 85, 125, 55],
+        [Some(Type::Grass), Some(Type::Psychic)],
+    ),
+    species("Cubone", [50, 50, 95, 40, 35], [Some(Type::Ground), None]),
+    species("Marowak", [60, 80, 110, 50, 45], [Some(Type::Ground), None]),
+    species(
+        "Hitmonlee",
+        [50, 120, 53, 35, 87],
+        [Some(Type::Fighting), None],
+    ),
+    species(
+        "Hitmonchan",
+        [50, 105, 79, 35, 76],
+        [Some(Type::Fighting), None],
+    ),
+    species(
+        "Lickitung",
+        [90, 55, 75, 60, 30],
+        [Some(Type::Normal), None],
+    ),
+    species("Koffing", [40, 65, 95, 60, 35], [Some(Type::Poison), None]),
+    species("Weezing", [65, 90, 120, 85, 60], [Some(Type::Poison), None]),
+    species(
+        "Rhyhorn",
+        [80, 85, 95, 30, 25],
+        [Some(Type::Ground), Some(Type::Rock)],
+    ),
+    species(
+        "Rhydon",
+        [105, 130, 120, 45, 40],
+        [Some(Type::Ground), Some(Type::Rock)],
+    ),
+    species("Chansey", [250, 5, 5, 105, 50], [Some(Type::Normal), None]),
+    species("Tangela", [65, 55, 115, 100, 60], [Some(Type::Grass), None]),
+    species(
+        "Kangaskhan",
+        [105, 95, 80, 40, 90],
+        [Some(Type::Normal), None],
+    ),
+    species("Horsea", [30, 40, 70, 70, 60], [Some(Type::Water), None]),
+    species("Seadra", [55, 65, 95, 95, 85], [Some(Type::Water), None]),
+    species("Goldeen", [45, 67, 60, 50, 63], [Some(Type::Water), None]),
+    species("Seaking", [80, 92, 65, 80, 68], [Some(Type::Water), None]),
+    species("Staryu", [30, 45, 55, 70, 85], [Some(Type::Water), None]),
+    species(
+        "Starmie",
+        [60, 75, 85, 100, 115],
+        [Some(Type::Water), Some(Type::Psychic)],
+    ),
+    species(
+        "Mr. Mime",
+        [40, 45, 65, 100, 90],
+        [Some(Type::Psychic), None],
+    ),
+    species(
+        "Scyther",
+        [70, 110, 80, 55, 105],
+        [Some(Type::Bug), Some(Type::Flying)],
+    ),
+    species(
+        "Jynx",
+        [65, 50, 35, 95, 95],
+        [Some(Type::Ice), Some(Type::Psychic)],
+    ),
+    species(
+        "Electabuzz",
+        [65, 83, 57, 85, 105],
+        [Some(Type::Electric), None],
+    ),
+    species("Magmar", [65, 95, 57, 85, 93], [Some(Type::Fire), None]),
+    species("Pinsir", [65, 125, 100, 55, 85], [Some(Type::Bug), None]),
+    species("Tauros", [75, 100, 95, 70, 110], [Some(Type::Normal), None]),
+    species("Magikarp", [20, 10, 55, 20, 80], [Some(Type::Water), None]),
+    species(
+        "Gyarados",
+        [95, 125, 79, 100, 81],
+        [Some(Type::Water), Some(Type::Flying)],
+    ),
+    species(
+        "Lapras",
+        [130, 85, 80, 95, 60],
+        [Some(Type::Water), Some(Type::Ice)],
+    ),
+    species("Ditto", [48, 48, 48, 48, 48], [Some(Type::Normal), None]),
+    species("Eevee", [55, 55, 50, 65, 55], [Some(Type::Normal), None]),
+    species(
+        "Vaporeon",
+        [130, 65, 60, 110, 65],
+        [Some(Type::Water), None],
+    ),
+    species(
+        "Jolteon",
+        [65, 65, 60, 110, 130],
+        [Some(Type::Electric), None],
+    ),
+    species("Flareon", [65, 130, 60, 110, 65], [Some(Type::Fire), None]),
+    species("Porygon", [65, 60, 70, 75, 40], [Some(Type::Normal), None]),
+    species(
+        "Omanyte",
+        [35, 40, 100, 90, 35],
+        [Some(Type::Rock), Some(Type::Water)],
+    ),
+    species(
+        "Omastar",
+        [70, 60, 125, 115, 55],
+        [Some(Type::Rock), Some(Type::Water)],
+    ),
+    species(
+        "Kabuto",
+        [30, 80, 90, 45, 55],
+        [Some(Type::Rock), Some(Type::Water)],
+    ),
+    species(
+        "Kabutops",
+        [60, 115, 105, 70, 80],
+        [Some(Type::Rock), Some(Type::Water)],
+    ),
+    species(
+        "Aerodactyl",
+        [80, 105, 65, 60, 130],
+        [Some(Type::Rock), Some(Type::Flying)],
+    ),
+    species(
+        "Snorlax",
+        [160, 110, 65, 65, 30],
+        [Some(Type::Normal), None],
+    ),
+    species(
+        "Articuno",
+        [90, 85, 100, 125, 85],
+        [Some(Type::Ice), Some(Type::Flying)],
+    ),
+    species(
+        "Zapdos",
+        [90, 90, 85, 125, 100],
+        [Some(Type::Electric), Some(Type::Flying)],
+    ),
+    species(
+        "Moltres",
+        [90, 100, 90, 125, 90],
+        [Some(Type::Fire), Some(Type::Flying)],
+    ),
+    species("Dratini", [41, 64, 45, 50, 50], [Some(Type::Dragon), None]),
+    species(
+        "Dragonair",
+        [61, 84, 65, 70, 70],
+        [Some(Type::Dragon), None],
+    ),
+    species(
+        "Dragonite",
+        [91, 134, 95, 100, 80],
+        [Some(Type::Dragon), Some(Type::Flying)],
+    ),
+    species(
+        "Mewtwo",
+        [106, 110, 90, 154, 130],
+        [Some(Type::Psychic), None],
+    ),
+    species(
+        "Mew",
+        [100, 100, 100, 100, 100],
+        [Some(Type::Psychic), None],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::Stat;
+
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!("Mr. Mime", find("mr mime").unwrap().name);
+        assert_eq!("Nidoran♀", find("Nidoran-F").unwrap().name);
+        assert_eq!("Farfetch'd", find("FARFETCHD").unwrap().name);
+        assert!(find("Pichu").is_none());
+    }
+
+    #[test]
+    fn test_pokemon() {
+        let mew = find("Mew").unwrap().pokemon(100);
+        assert_eq!(403, mew.get_stat(Stat::HP));
+    }
+}
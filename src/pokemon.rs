@@ -1,14 +1,16 @@
-use crate::stats::{PERFECT_EVS, PERFECT_IVS, Stat, StatSet};
+use crate::moves::MoveSlot;
+use crate::stats::{Stat, StatSet, PERFECT_EVS, PERFECT_IVS};
 use crate::types::Type;
 use crate::utils::IntegerSquareRoot;
 
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq, Hash)]
 pub struct Pokemon {
     pub level: u8,
     pub base_stats: StatSet,
     pub evs: StatSet,
     pub ivs: StatSet,
     pub types: [Option<Type>; 2],
+    pub moves: [Option<MoveSlot>; 4],
 }
 
 impl Pokemon {
@@ -26,7 +28,7 @@ impl Pokemon {
                 let bs = self.base_stats[usize::from(stat)];
                 let iv = self.ivs[usize::from(stat)];
 
-                let s = ev.saturating_sub(1).isqrt() + 1;
+                let s = IntegerSquareRoot::isqrt(ev.saturating_sub(1)) + 1;
                 let s = s / 4;
                 let s = s.min(63);
                 let s = s + 2 * (iv + bs);
@@ -40,7 +42,7 @@ impl Pokemon {
 
     /// Check if this pokemon has the wanted type.
     pub fn has_type(&self, wanted: Type) -> bool {
-        self.types.iter().any(|&x| x == Some(wanted))
+        self.types.contains(&Some(wanted))
     }
 
     pub fn get_types(&self) -> &[Option<Type>] {
@@ -50,8 +52,8 @@ impl Pokemon {
 
 /// Generic pokemon stats.
 ///
-/// The default pokemon has the base stats and type of Rattata, perfect IVs and EVs, and is
-/// at level 100. Override as needed.
+/// The default pokemon has the base stats and type of Rattata, perfect IVs and EVs, no moves,
+/// and is at level 100. Override as needed.
 impl Default for Pokemon {
     fn default() -> Self {
         Pokemon {
@@ -60,6 +62,7 @@ impl Default for Pokemon {
             evs: PERFECT_EVS,
             ivs: PERFECT_IVS,
             types: [Some(Type::Normal), None],
+            moves: [None; 4],
         }
     }
 }
@@ -0,0 +1,362 @@
+//! Playing out battles between players, and estimating win rates over many battles.
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::battle::{Action, Battle, Event, Ruleset};
+use crate::player::Player;
+use crate::pokemon::Pokemon;
+
+/// Play out a battle until it is over, or until the maximum number of turns has passed.
+///
+/// Every event is passed to `observe`, together with the number of the turn it happened in.
+/// Replacements for fainted pokemon are sent out at the end of the turn. Returns the number of
+/// turns played.
+pub fn play(
+    battle: &mut Battle,
+    players: [&mut dyn Player; 2],
+    rand: &mut impl Rng,
+    max_turns: u32,
+    mut observe: impl FnMut(u32, Event),
+) -> u32 {
+    let [first, second] = players;
+    let mut turn = 0;
+
+    while !battle.is_over() && turn < max_turns {
+        turn += 1;
+
+        let actions = [first.choose(battle, 0), second.choose(battle, 1)];
+        for event in battle.turn(rand, actions) {
+            observe(turn, event);
+        }
+
+        for (side, player) in [&mut *first, &mut *second].iter_mut().enumerate() {
+            if battle.must_replace(side) {
+                if let Some(index) = choose_replacement(&mut **player, battle, side) {
+                    observe(turn, battle.replace(side, index));
+                }
+            }
+        }
+    }
+
+    turn
+}
+
+/// Ask a player which pokemon to send out after the active one fainted.
+///
+/// Only switches are allowed here, so any other answer falls back to the first pokemon that can
+/// come in. Returns None if there is no pokemon left.
+pub fn choose_replacement(player: &mut dyn Player, battle: &Battle, side: usize) -> Option<usize> {
+    match player.choose(battle, side) {
+        Action::Switch(index) => Some(index),
+        _ => (0..battle.team_size(side)).find(|&i| battle.can_switch(side, i)),
+    }
+}
+
+/// Settings for estimating win rates.
+#[derive(Copy, Clone, Debug)]
+pub struct Simulation {
+    /// Number of battles to play.
+    pub battles: u32,
+    /// Seed for the first battle. Every battle gets its own seed derived from this one.
+    pub seed: u64,
+    /// Battles that last longer than this are counted as a draw.
+    pub max_turns: u32,
+    pub ruleset: Ruleset,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation {
+            battles: 1000,
+            seed: 0,
+            max_turns: 1000,
+            ruleset: Ruleset::Cartridge,
+        }
+    }
+}
+
+/// Estimate of a rate, with a 95% confidence interval.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Estimate {
+    pub rate: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Estimate {
+    /// Estimate a rate from a number of successes out of a number of trials.
+    ///
+    /// This uses the Wilson score interval, which behaves well for rates close to 0 or 1.
+    pub fn new(successes: u32, trials: u32) -> Self {
+        if trials == 0 {
+            return Estimate {
+                rate: 0.0,
+                lower: 0.0,
+                upper: 1.0,
+            };
+        }
+
+        const Z: f64 = 1.96;
+        let n = f64::from(trials);
+        let rate = f64::from(successes) / n;
+        let denominator = 1.0 + Z * Z / n;
+        let center = (rate + Z * Z / (2.0 * n)) / denominator;
+        let margin = Z * (rate * (1.0 - rate) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+
+        Estimate {
+            rate,
+            lower: (center - margin).max(0.0),
+            upper: (center + margin).min(1.0),
+        }
+    }
+}
+
+/// Results of many battles between the same two teams.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WinRates {
+    pub battles: u32,
+    /// Battles won by either side.
+    pub wins: [u32; 2],
+    /// Battles without a winner, because both sides ran out of pokemon at once or the battle
+    /// took too long.
+    pub draws: u32,
+    /// Draws because the battle took too long.
+    pub timeouts: u32,
+    pub total_turns: u64,
+    /// Pokemon knocked out by each team member. A knockout is credited to the active pokemon of
+    /// the opposing side, even if the pokemon fainted from recoil or residual damage.
+    pub knockouts: [Vec<u32>; 2],
+    /// Number of times each team member fainted.
+    pub faints: [Vec<u32>; 2],
+}
+
+impl WinRates {
+    fn new(team_sizes: [usize; 2]) -> Self {
+        WinRates {
+            knockouts: team_sizes.map(|size| vec![0; size]),
+            faints: team_sizes.map(|size| vec![0; size]),
+            ..Default::default()
+        }
+    }
+
+    /// Get the rate at which the given side wins.
+    pub fn win_rate(&self, side: usize) -> Estimate {
+        Estimate::new(self.wins[side], self.battles)
+    }
+
+    pub fn draw_rate(&self) -> Estimate {
+        Estimate::new(self.draws, self.battles)
+    }
+
+    pub fn average_turns(&self) -> f64 {
+        if self.battles == 0 {
+            0.0
+        } else {
+            self.total_turns as f64 / f64::from(self.battles)
+        }
+    }
+
+    /// Get the same results, seen from the other side.
+    fn swapped(mut self) -> Self {
+        self.wins.swap(0, 1);
+        self.knockouts.swap(0, 1);
+        self.faints.swap(0, 1);
+        self
+    }
+
+    fn merge(mut self, other: WinRates) -> Self {
+        self.battles += other.battles;
+        self.draws += other.draws;
+        self.timeouts += other.timeouts;
+        self.total_turns += other.total_turns;
+        for side in 0..2 {
+            self.wins[side] += other.wins[side];
+            let counts = self.knockouts[side].iter_mut().zip(&other.knockouts[side]);
+            counts.for_each(|(total, count)| *total += count);
+            let counts = self.faints[side].iter_mut().zip(&other.faints[side]);
+            counts.for_each(|(total, count)| *total += count);
+        }
+        self
+    }
+}
+
+/// Play a single battle, and record the results.
+fn simulate(
+    first: &[Pokemon],
+    second: &[Pokemon],
+    players: &(impl Fn(usize, u64) -> Box<dyn Player> + Sync),
+    config: &Simulation,
+    index: u32,
+) -> WinRates {
+    let mut seeds = StdRng::seed_from_u64(config.seed.wrapping_add(u64::from(index)));
+    let mut first_player = players(0, seeds.gen());
+    let mut second_player = players(1, seeds.gen());
+    let mut rand = StdRng::seed_from_u64(seeds.gen());
+
+    let mut battle = Battle::with_teams(first, second, config.ruleset);
+    let mut results = WinRates::new([first.len(), second.len()]);
+    let mut active = [0, 0];
+    let turns = play(
+        &mut battle,
+        [first_player.as_mut(), second_player.as_mut()],
+        &mut rand,
+        config.max_turns,
+        |_, event| match event {
+            Event::Fainted(side) => {
+                results.faints[side][active[side]] += 1;
+                results.knockouts[1 - side][active[1 - side]] += 1;
+            }
+            Event::SwitchedIn(side, index) => active[side] = index,
+            _ => (),
+        },
+    );
+
+    results.battles = 1;
+    results.total_turns = u64::from(turns);
+    match battle.winner() {
+        Some(side) => results.wins[side] = 1,
+        None => {
+            results.draws = 1;
+            if !battle.is_over() {
+                results.timeouts = 1;
+            }
+        }
+    }
+
+    results
+}
+
+/// Play many battles between two teams in parallel, and collect the results.
+///
+/// `players` creates the player for a side, given the side and a seed. Every battle gets new
+/// players. The results only depend on the seed in the settings, not on the number of threads.
+pub fn win_rates(
+    first: &[Pokemon],
+    second: &[Pokemon],
+    players: impl Fn(usize, u64) -> Box<dyn Player> + Sync,
+    config: &Simulation,
+) -> WinRates {
+    let empty = || WinRates::new([first.len(), second.len()]);
+
+    (0..config.battles)
+        .into_par_iter()
+        .map(|index| simulate(first, second, &players, config, index))
+        .reduce(empty, WinRates::merge)
+}
+
+/// Play one on one battles between every pair of pokemon.
+///
+/// The entry at `[i][j]` holds the results of `pokemon[i]` as the first side against
+/// `pokemon[j]`. Only the battles with `i <= j` are played, the others are the same results seen
+/// from the other side. Every pair gets its own seed, derived from the seed in the settings.
+pub fn matchup_matrix(
+    pokemon: &[Pokemon],
+    players: impl Fn(usize, u64) -> Box<dyn Player> + Sync,
+    config: &Simulation,
+) -> Vec<Vec<WinRates>> {
+    let pairs: Vec<_> = (0..pokemon.len())
+        .flat_map(|i| (i..pokemon.len()).map(move |j| (i, j)))
+        .collect();
+    let results: Vec<_> = pairs
+        .par_iter()
+        .map(|&(i, j)| {
+            let pair = ((i as u64) << 32) | j as u64;
+            let config = Simulation {
+                seed: StdRng::seed_from_u64(config.seed ^ pair).gen(),
+                ..*config
+            };
+            let first = std::slice::from_ref(&pokemon[i]);
+            let second = std::slice::from_ref(&pokemon[j]);
+            win_rates(first, second, &players, &config)
+        })
+        .collect();
+
+    let mut matrix = vec![vec![WinRates::default(); pokemon.len()]; pokemon.len()];
+    for (&(i, j), results) in pairs.iter().zip(results) {
+        matrix[j][i] = results.clone().swapped();
+        matrix[i][j] = results;
+    }
+
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::movedex;
+    use crate::player::RandomPlayer;
+
+    use super::*;
+
+    #[test]
+    fn test_estimate() {
+        let estimate = Estimate::new(50, 100);
+        assert_eq!(0.5, estimate.rate);
+        assert!((estimate.lower - 0.4038).abs() < 1e-4);
+        assert!((estimate.upper - 0.5962).abs() < 1e-4);
+
+        let estimate = Estimate::new(0, 10);
+        assert_eq!(0.0, estimate.lower);
+        assert!(estimate.upper > 0.0);
+    }
+
+    #[test]
+    fn test_win_rates() {
+        let team = [Pokemon::default(), Pokemon::default()];
+        let config = Simulation {
+            battles: 50,
+            ..Default::default()
+        };
+        let players = |_, seed| -> Box<dyn Player> {
+            Box::new(RandomPlayer::new(StdRng::seed_from_u64(seed)))
+        };
+
+        let results = win_rates(&team, &team, players, &config);
+        assert_eq!(50, results.battles);
+        assert_eq!(50, results.wins[0] + results.wins[1] + results.draws);
+        let faints: u32 = results.faints.iter().flatten().sum();
+        let knockouts: u32 = results.knockouts.iter().flatten().sum();
+        assert_eq!(faints, knockouts);
+
+        // The same seed gives the same results.
+        assert_eq!(results, win_rates(&team, &team, players, &config));
+    }
+
+    #[test]
+    fn test_matchup_matrix() {
+        let pokemon = [
+            Pokemon::default(),
+            Pokemon {
+                level: 50,
+                ..Default::default()
+            },
+        ];
+        let config = Simulation {
+            battles: 20,
+            ..Default::default()
+        };
+        let players = |_, seed| -> Box<dyn Player> {
+            Box::new(RandomPlayer::new(StdRng::seed_from_u64(seed)))
+        };
+
+        let matrix = matchup_matrix(&pokemon, players, &config);
+        assert_eq!(20, matrix[0][1].wins[0]);
+        assert_eq!(matrix[0][1].wins[0], matrix[1][0].wins[1]);
+        assert_eq!(20, matrix[1][1].battles);
+    }
+
+    #[test]
+    fn test_choose_replacement() {
+        struct Stubborn;
+        impl Player for Stubborn {
+            fn choose(&mut self, _: &Battle, _: usize) -> Action {
+                Action::UseMove(&movedex::TACKLE)
+            }
+        }
+
+        let team = [Pokemon::default(), Pokemon::default(), Pokemon::default()];
+        let mut battle = Battle::with_teams(&team, &team, Ruleset::Cartridge);
+        battle.side_mut(0).damage_direct(999);
+        assert_eq!(Some(1), choose_replacement(&mut Stubborn, &battle, 0));
+    }
+}
@@ -0,0 +1,487 @@
+//! Solving small battles by walking through every possible outcome of the next few turns.
+//!
+//! Every turn is a zero-sum game where both sides choose at the same time, followed by the
+//! random outcome of their actions. The solver computes the expected result of every state it
+//! reaches within a limited number of turns, and the mixed strategies that are optimal for both
+//! sides. States beyond that are valued by a heuristic, so the result is only exact when every
+//! battle is over in time.
+use std::collections::HashMap;
+
+use num::PrimInt;
+use rand::distributions::uniform::SampleUniform;
+
+use crate::battle::{Action, Battle};
+use crate::chance::Chance;
+use crate::stats::Stat;
+
+const EPSILON: f64 = 1e-12;
+
+/// Walks through every outcome of the random decisions in a turn, one run at a time.
+///
+/// Every run makes the same decisions as the previous one, except for the last decision that has
+/// options left, which moves on to its next option.
+struct Outcomes {
+    /// Chosen option and number of options for every decision made in the current run.
+    decisions: Vec<(usize, usize)>,
+    /// Number of decisions made in the current run.
+    position: usize,
+    /// Probability of the decisions made in the current run.
+    probability: f64,
+}
+
+impl Outcomes {
+    fn new() -> Self {
+        Outcomes {
+            decisions: Vec::new(),
+            position: 0,
+            probability: 1.0,
+        }
+    }
+
+    /// Make a decision between a number of options.
+    fn decide(&mut self, options: usize) -> usize {
+        if self.position == self.decisions.len() {
+            self.decisions.push((0, options));
+        }
+
+        let (choice, _) = self.decisions[self.position];
+        self.position += 1;
+        choice
+    }
+
+    /// Start the next run. Returns false if every outcome has been visited.
+    fn advance(&mut self) -> bool {
+        self.position = 0;
+        self.probability = 1.0;
+
+        while let Some((choice, options)) = self.decisions.pop() {
+            if choice + 1 < options {
+                self.decisions.push((choice + 1, options));
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl Chance for Outcomes {
+    fn chance(&mut self, numerator: u8) -> bool {
+        if numerator == 0 {
+            return false;
+        }
+
+        let p = f64::from(numerator) / 256.0;
+        let success = self.decide(2) == 0;
+        self.probability *= if success { p } else { 1.0 - p };
+        success
+    }
+
+    fn coin(&mut self) -> bool {
+        self.probability /= 2.0;
+        self.decide(2) == 0
+    }
+
+    fn byte(&mut self) -> u8 {
+        self.probability /= 256.0;
+        self.decide(256) as u8
+    }
+
+    fn range<T: SampleUniform + PrimInt>(&mut self, low: T, high: T) -> T {
+        let options = (high - low).to_usize().expect("Empty range");
+        self.probability /= options as f64;
+        low + num::cast(self.decide(options)).unwrap()
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        self.probability /= items.len() as f64;
+        Some(&items[self.decide(items.len())])
+    }
+}
+
+/// Get every possible state after a turn, with its probability.
+///
+/// Outcomes that lead to the same state are merged.
+pub fn outcomes<'a>(battle: &Battle<'a>, actions: [Action; 2]) -> Vec<(Battle<'a>, f64)> {
+    let mut outcomes: Vec<(Battle<'a>, f64)> = Vec::new();
+    let mut indices: HashMap<Battle<'a>, usize> = HashMap::new();
+    let mut rand = Outcomes::new();
+
+    loop {
+        let mut next = battle.clone();
+        next.turn(&mut rand, actions);
+
+        match indices.get(&next) {
+            Some(&index) => outcomes[index].1 += rand.probability,
+            None => {
+                indices.insert(next.clone(), outcomes.len());
+                outcomes.push((next, rand.probability));
+            }
+        }
+
+        if !rand.advance() {
+            return outcomes;
+        }
+    }
+}
+
+/// Solve a zero-sum game, where the first player picks a row to maximize the payoff and the second
+/// player picks a column to minimize it.
+///
+/// Returns the value of the game, and the optimal mixed strategies of both players. This uses the
+/// simplex method with Bland's rule on the usual linear program, after shifting all payoffs to be
+/// positive.
+fn solve_game(payoff: &[Vec<f64>]) -> (f64, Vec<f64>, Vec<f64>) {
+    let rows = payoff.len();
+    let columns = payoff[0].len();
+    let min = payoff
+        .iter()
+        .flatten()
+        .fold(f64::INFINITY, |min, &p| min.min(p));
+    let shift = 1.0 - min;
+
+    // Maximize the sum of y subject to A y <= 1, with a slack variable for every row. The last
+    // entry of every line is its right hand side.
+    let width = columns + rows + 1;
+    let mut tableau: Vec<Vec<f64>> = payoff
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line = vec![0.0; width];
+            for (j, &p) in row.iter().enumerate() {
+                line[j] = p + shift;
+            }
+            line[columns + i] = 1.0;
+            line[width - 1] = 1.0;
+            line
+        })
+        .collect();
+    let mut objective = vec![0.0; width];
+    objective[..columns].iter_mut().for_each(|c| *c = -1.0);
+    let mut basis: Vec<usize> = (columns..columns + rows).collect();
+
+    while let Some(entering) = (0..width - 1).find(|&j| objective[j] < -EPSILON) {
+        let ratio = |i: usize| tableau[i][width - 1] / tableau[i][entering];
+        let leaving = (0..rows)
+            .filter(|&i| tableau[i][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let order = ratio(a).partial_cmp(&ratio(b)).unwrap();
+                order.then(basis[a].cmp(&basis[b]))
+            })
+            .expect("Payoffs are positive, so the program is bounded");
+
+        let pivot = tableau[leaving][entering];
+        tableau[leaving].iter_mut().for_each(|x| *x /= pivot);
+        let pivot_line = tableau[leaving].clone();
+        let lines = tableau
+            .iter_mut()
+            .enumerate()
+            .filter(|&(i, _)| i != leaving);
+        for line in lines.map(|(_, line)| line).chain(Some(&mut objective)) {
+            let factor = line[entering];
+            line.iter_mut()
+                .zip(&pivot_line)
+                .for_each(|(x, p)| *x -= factor * p);
+        }
+        basis[leaving] = entering;
+    }
+
+    // The optimal sum is the inverse of the shifted value. The strategy of the first player
+    // follows from the dual solution, found in the objective under the slack variables.
+    let total = objective[width - 1];
+    let mut second = vec![0.0; columns];
+    for (i, &variable) in basis.iter().enumerate() {
+        if variable < columns {
+            second[variable] = (tableau[i][width - 1] / total).max(0.0);
+        }
+    }
+    let first = (0..rows)
+        .map(|i| (objective[columns + i] / total).max(0.0))
+        .collect();
+
+    (1.0 / total - shift, first, second)
+}
+
+/// Optimal play from a state of a battle.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    /// Expected result for the first side, counting a win as 1, a draw as 0.5 and a loss as 0.
+    pub value: f64,
+    /// Whether the value is exact. If not, some states were valued by the hit points both sides
+    /// had left, because the battle wasn't over within the solver's depth.
+    pub exact: bool,
+    /// Probability of every action for either side. Empty if the side has nothing to choose,
+    /// like when only the other side has to replace a fainted pokemon.
+    pub strategies: [Vec<(Action, f64)>; 2],
+}
+
+/// Depth-limited search for the optimal strategies in a battle.
+///
+/// This does not solve the whole battle, which could go on forever. States that are not over
+/// after the last turn are valued by the hit points both sides have left, a heuristic that makes
+/// the solution approximate. `Solution::exact` tells whether that happened. Values are memoized,
+/// so the solver can be reused for states of the same battle.
+pub struct Solver<'a> {
+    depth: u32,
+    /// Value of every state at a remaining depth, and whether the value is exact.
+    values: HashMap<(Battle<'a>, u32), (f64, bool)>,
+}
+
+impl<'a> Solver<'a> {
+    /// Create a solver that looks the given number of turns ahead.
+    ///
+    /// Panics if the depth is zero, since the solver has to play at least one turn to find the
+    /// strategies.
+    pub fn new(depth: u32) -> Self {
+        assert!(depth > 0, "The solver must look at least one turn ahead");
+        Solver {
+            depth,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Find the optimal strategies for both sides, and the value of the battle.
+    pub fn solve(&mut self, battle: &Battle<'a>) -> Solution {
+        if battle.is_over() {
+            Solution {
+                value: result(battle),
+                exact: true,
+                strategies: [Vec::new(), Vec::new()],
+            }
+        } else {
+            self.solve_state(battle, self.depth)
+        }
+    }
+
+    /// Number of states the solver has memoized.
+    pub fn states(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Get the value of a state, and whether it is exact.
+    fn value(&mut self, battle: &Battle<'a>, depth: u32) -> (f64, bool) {
+        if battle.is_over() {
+            return (result(battle), true);
+        }
+
+        let replacing = (0..2).any(|side| battle.must_replace(side));
+        if depth == 0 && !replacing {
+            return (estimate(battle), false);
+        }
+
+        let key = (battle.clone(), depth);
+        if let Some(&value) = self.values.get(&key) {
+            return value;
+        }
+
+        let solution = self.solve_state(battle, depth);
+        let value = (solution.value, solution.exact);
+        self.values.insert(key, value);
+        value
+    }
+
+    fn solve_state(&mut self, battle: &Battle<'a>, depth: u32) -> Solution {
+        // Replacing fainted pokemon happens between turns, and doesn't count as a turn.
+        let replacing = (0..2).any(|side| battle.must_replace(side));
+        let options = choices(battle);
+        let mut exact = true;
+
+        let mut payoff = Vec::new();
+        for &first in &options[0] {
+            let mut row = Vec::new();
+            for &second in &options[1] {
+                let value = match (first, second) {
+                    (Some(first), Some(second)) if !replacing => {
+                        let mut value = 0.0;
+                        for (next, p) in outcomes(battle, [first, second]) {
+                            let (next_value, next_exact) = self.value(&next, depth - 1);
+                            value += p * next_value;
+                            exact &= next_exact;
+                        }
+                        value
+                    }
+                    _ => {
+                        let mut next = battle.clone();
+                        for (side, &action) in [first, second].iter().enumerate() {
+                            if let Some(Action::Switch(index)) = action {
+                                next.replace(side, index);
+                            }
+                        }
+                        let (value, next_exact) = self.value(&next, depth);
+                        exact &= next_exact;
+                        value
+                    }
+                };
+                row.push(value);
+            }
+            payoff.push(row);
+        }
+
+        let (value, first, second) = solve_game(&payoff);
+        let strategy = |options: &[Option<Action>], probabilities: Vec<f64>| {
+            options
+                .iter()
+                .zip(probabilities)
+                .filter_map(|(action, p)| action.map(|action| (action, p)))
+                .collect()
+        };
+
+        Solution {
+            value,
+            exact,
+            strategies: [strategy(&options[0], first), strategy(&options[1], second)],
+        }
+    }
+}
+
+/// Get the choices of both sides.
+///
+/// When a fainted pokemon has to be replaced, only the sides replacing their pokemon choose. The
+/// other side has a single choice of doing nothing, which is None.
+pub(crate) fn choices(battle: &Battle) -> [Vec<Option<Action>>; 2] {
+    let replacing = (0..2).any(|side| battle.must_replace(side));
+    [0, 1].map(|side| {
+        if replacing && !battle.must_replace(side) {
+            vec![None]
+        } else {
+            let actions = battle.available_actions(side);
+            actions.into_iter().map(Some).collect()
+        }
+    })
+}
+
+/// Value of a battle that is over.
+pub(crate) fn result(battle: &Battle) -> f64 {
+    match battle.winner() {
+        Some(0) => 1.0,
+        Some(_) => 0.0,
+        None => 0.5,
+    }
+}
+
+/// Estimate the value of a battle from the fraction of hit points both sides have left.
+pub(crate) fn estimate(battle: &Battle) -> f64 {
+    let fraction = |side| {
+        let members = (0..battle.team_size(side)).map(|index| battle.member(side, index));
+        let (left, total) = members.fold((0.0, 0.0), |(left, total), member| {
+            let max = f64::from(member.pokemon().get_stat(Stat::HP));
+            (left + f64::from(member.hit_points()), total + max)
+        });
+        left / total
+    };
+
+    0.5 + (fraction(0) - fraction(1)) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::battle::Ruleset;
+    use crate::movedex;
+    use crate::moves::{Move, MoveSlot};
+    use crate::pokemon::Pokemon;
+
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "{} != {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_solve_game() {
+        // Matching pennies.
+        let (value, first, second) = solve_game(&[vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert_close(0.5, value);
+        assert_close(0.5, first[0]);
+        assert_close(0.5, second[1]);
+
+        // The second row is dominated.
+        let (value, first, second) = solve_game(&[vec![3.0, 1.0], vec![2.0, 0.0]]);
+        assert_close(1.0, value);
+        assert_close(1.0, first[0]);
+        assert_close(1.0, second[1]);
+
+        // Rock, paper, scissors with a bonus for winning with rock.
+        let game = [
+            vec![0.0, -1.0, 2.0],
+            vec![1.0, 0.0, -1.0],
+            vec![-1.0, 1.0, 0.0],
+        ];
+        let (value, first, _) = solve_game(&game);
+        assert_close(1.0 / 12.0, value);
+        assert_close(1.0, first.iter().sum());
+    }
+
+    fn pokemon(moves: &[&'static Move]) -> Pokemon {
+        let mut pokemon = Pokemon {
+            level: 20,
+            ..Default::default()
+        };
+        for (slot, &known) in pokemon.moves.iter_mut().zip(moves) {
+            *slot = Some(MoveSlot::new(known));
+        }
+        pokemon
+    }
+
+    #[test]
+    fn test_outcomes() {
+        let team = [pokemon(&[&movedex::SONIC_BOOM])];
+        let battle = Battle::with_teams(&team, &team, Ruleset::Cartridge);
+
+        let sonic_boom = Action::UseMove(&movedex::SONIC_BOOM);
+        let outcomes = outcomes(&battle, [sonic_boom, sonic_boom]);
+        // Both sides hit or miss, and the order doesn't matter.
+        assert_eq!(4, outcomes.len());
+        assert_close(1.0, outcomes.iter().map(|(_, p)| p).sum());
+    }
+
+    #[test]
+    fn test_solver() {
+        let team = [pokemon(&[&movedex::GROWL, &movedex::SONIC_BOOM])];
+        let battle = Battle::with_teams(&team, &team, Ruleset::Cartridge);
+
+        let mut solver = Solver::new(3);
+        let solution = solver.solve(&battle);
+        assert_close(0.5, solution.value);
+        assert!(!solution.exact);
+        assert!(solver.states() > 0);
+        for strategy in &solution.strategies {
+            let (used, p) = strategy[1];
+            assert!(matches!(used, Action::UseMove(used) if used.name() == "Sonic Boom"));
+            assert_close(1.0, p);
+        }
+
+        // A side that can't do any damage loses, unless the other side misses too often.
+        let harmless = [pokemon(&[&movedex::GROWL])];
+        let battle = Battle::with_teams(&team, &harmless, Ruleset::Cartridge);
+        let solution = Solver::new(4).solve(&battle);
+        assert!(solution.value > 0.95);
+
+        // Swift never misses, so this battle is over within a single turn and solved exactly.
+        let mut strong = pokemon(&[&movedex::SWIFT]);
+        strong.level = 100;
+        let strong = [strong];
+        let weak = [Pokemon {
+            level: 2,
+            ..pokemon(&[&movedex::GROWL])
+        }];
+        let battle = Battle::with_teams(&strong, &weak, Ruleset::Cartridge);
+        let solution = Solver::new(1).solve(&battle);
+        assert!(solution.exact);
+        assert_close(1.0, solution.value);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_solver_depth_zero() {
+        Solver::new(0);
+    }
+}
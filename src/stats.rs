@@ -2,7 +2,7 @@ use std::ops::Add;
 
 use num::rational::Ratio;
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Stat {
     HP,
     Attack,
@@ -37,22 +37,42 @@ pub const PERFECT_IVS: StatSet = [0xf; 5];
 /// Maximum attainable effort values.
 pub const PERFECT_EVS: StatSet = [0xffff; 5];
 
+/// Compute the HP IV, which is derived from the lowest bits of the other IVs.
+///
+/// # Example
+///
+/// ```
+/// use genwunsym::stats::hp_iv;
+/// assert_eq!(15, hp_iv(&[0, 15, 15, 15, 15]));
+/// assert_eq!(0b1010, hp_iv(&[0, 15, 14, 14, 13]));
+/// ```
+pub fn hp_iv(ivs: &StatSet) -> u16 {
+    let bit = |stat: Stat, shift: u16| (ivs[usize::from(stat)] & 1) << shift;
+    bit(Stat::Attack, 3) | bit(Stat::Defense, 2) | bit(Stat::Speed, 1) | bit(Stat::Special, 0)
+}
+
 /// Stat boost modifier.
 ///
 /// Represents any of the 13 levels of stat boost; 6 levels in either
 /// direction.
 ///
 /// Additions on this object are defined as saturating at the boundaries.
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
 pub struct Modifier(i8);
 
 impl Modifier {
-    pub fn modify(self, stat: u8) -> u16 {
-        let stat = Ratio::from_integer(u16::from(stat));
+    /// Apply this modifier to a stat.
+    ///
+    /// As in RBY, the result is kept between 1 and 999.
+    pub fn modify(self, stat: u16) -> u16 {
+        let ratio = self.get_ratio();
+        let result = u32::from(stat) * u32::from(*ratio.numer()) / u32::from(*ratio.denom());
+        result.clamp(1, 999) as u16
+    }
 
-        let multiplier = self.get_ratio();
-        let result = stat * multiplier;
-        result.to_integer().min(999)
+    /// Get the level of boost, between -6 and 6.
+    pub fn level(self) -> i8 {
+        self.0
     }
 
     /// Get the ratio of the modifier for this level of boost.
@@ -70,7 +90,7 @@ impl Modifier {
             -2 => Ratio::new(1, 2),
             -1 => Ratio::new(66, 100),
             // Neutral and positive cases follow a nice pattern.
-            x => Ratio::new(x as u16 + 2, 2)
+            x => Ratio::new(x as u16 + 2, 2),
         }
     }
 }
@@ -97,7 +117,7 @@ impl Add<Modifier> for Modifier {
 
 impl From<i8> for Modifier {
     fn from(level: i8) -> Self {
-        let level = level.max(-6).min(6);
+        let level = level.clamp(-6, 6);
         Modifier(level)
     }
 }
@@ -0,0 +1,459 @@
+//! Teams of pokemon, and reading them from Showdown exports or JSON.
+use std::error::Error;
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::movedex;
+use crate::moves::{Move, MoveSlot};
+use crate::pokedex::{self, Species};
+use crate::pokemon::Pokemon;
+use crate::stats::{hp_iv, Stat, StatSet, PERFECT_EVS, PERFECT_IVS};
+
+/// Maximum number of pokemon in a team.
+pub const MAX_TEAM_SIZE: usize = 6;
+
+/// Error when reading a team.
+#[derive(Debug)]
+pub enum TeamError {
+    UnknownSpecies(String),
+    UnknownMove(String),
+    TooManyMoves(String),
+    TooManyMembers,
+    Empty,
+    InvalidLevel(u8),
+    /// A line of a Showdown export that couldn't be understood, with its line number.
+    InvalidLine(usize, String),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for TeamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TeamError::UnknownSpecies(name) => write!(f, "Unknown pokemon: {}", name),
+            TeamError::UnknownMove(name) => write!(f, "Unknown move: {}", name),
+            TeamError::TooManyMoves(name) => write!(f, "{} knows more than 4 moves", name),
+            TeamError::TooManyMembers => {
+                write!(f, "A team has at most {} pokemon", MAX_TEAM_SIZE)
+            }
+            TeamError::Empty => write!(f, "The team is empty"),
+            TeamError::InvalidLevel(level) => write!(f, "Invalid level: {}", level),
+            TeamError::InvalidLine(number, line) => {
+                write!(f, "Invalid line {}: {}", number, line)
+            }
+            TeamError::Json(error) => write!(f, "Invalid JSON: {}", error),
+        }
+    }
+}
+
+impl Error for TeamError {}
+
+impl From<serde_json::Error> for TeamError {
+    fn from(error: serde_json::Error) -> Self {
+        TeamError::Json(error)
+    }
+}
+
+/// A pokemon in a team, as read from a team file.
+#[derive(Debug)]
+pub struct TeamMember {
+    pub species: &'static Species,
+    pub nickname: Option<String>,
+    pub level: u8,
+    /// Individual values. The HP value is derived from the others.
+    pub ivs: StatSet,
+    /// Effort values, or stat experience.
+    pub evs: StatSet,
+    pub moves: Vec<&'static Move>,
+}
+
+impl TeamMember {
+    /// Create a level 100 member of a species, with perfect IVs and EVs and no moves.
+    pub fn new(species: &'static Species) -> Self {
+        TeamMember {
+            species,
+            nickname: None,
+            level: 100,
+            ivs: PERFECT_IVS,
+            evs: PERFECT_EVS,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Get the name to show for this pokemon, its nickname if it has one.
+    pub fn name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(self.species.name)
+    }
+
+    /// Create the pokemon to battle with.
+    pub fn pokemon(&self) -> Pokemon {
+        let mut moves = [None; 4];
+        for (slot, &known) in moves.iter_mut().zip(&self.moves) {
+            *slot = Some(MoveSlot::new(known));
+        }
+
+        Pokemon {
+            ivs: self.ivs,
+            evs: self.evs,
+            moves,
+            ..self.species.pokemon(self.level)
+        }
+    }
+
+    fn set_level(&mut self, level: u8) -> Result<(), TeamError> {
+        if level == 0 || level > 100 {
+            return Err(TeamError::InvalidLevel(level));
+        }
+
+        self.level = level;
+        Ok(())
+    }
+
+    fn set_ivs(&mut self, ivs: StatSet) {
+        self.ivs = ivs;
+        self.ivs[usize::from(Stat::HP)] = hp_iv(&ivs);
+    }
+
+    fn learn(&mut self, name: &str) -> Result<(), TeamError> {
+        let known = movedex::find(name).ok_or_else(|| TeamError::UnknownMove(name.to_owned()))?;
+        if self.moves.len() == 4 {
+            return Err(TeamError::TooManyMoves(self.name().to_owned()));
+        }
+
+        self.moves.push(known);
+        Ok(())
+    }
+}
+
+fn find_species(name: &str) -> Result<&'static Species, TeamError> {
+    pokedex::find(name).ok_or_else(|| TeamError::UnknownSpecies(name.to_owned()))
+}
+
+fn check_size(team: Vec<TeamMember>) -> Result<Vec<TeamMember>, TeamError> {
+    match team.len() {
+        0 => Err(TeamError::Empty),
+        n if n > MAX_TEAM_SIZE => Err(TeamError::TooManyMembers),
+        _ => Ok(team),
+    }
+}
+
+/// Read a team, either in JSON or as a Showdown export.
+///
+/// Anything that starts with `[` is read as JSON.
+pub fn parse(text: &str) -> Result<Vec<TeamMember>, TeamError> {
+    check_size(parse_sets(text)?)
+}
+
+/// Read any number of sets, in the same formats as a team.
+pub fn parse_sets(text: &str) -> Result<Vec<TeamMember>, TeamError> {
+    if text.trim_start().starts_with('[') {
+        parse_json(text)
+    } else {
+        parse_showdown(text)
+    }
+}
+
+/// Common sets for the pokemon of the standard RBY OU tier, as a Showdown export.
+pub const OU_SETS: &str = "\
+Alakazam
+- Psychic
+- Seismic Toss
+- Thunder Wave
+- Recover
+
+Chansey
+- Ice Beam
+- Thunderbolt
+- Thunder Wave
+- Soft-Boiled
+
+Cloyster
+- Clamp
+- Blizzard
+- Explosion
+- Hyper Beam
+
+Exeggutor
+- Sleep Powder
+- Psychic
+- Explosion
+- Stun Spore
+
+Gengar
+- Hypnosis
+- Thunderbolt
+- Night Shade
+- Explosion
+
+Golem
+- Earthquake
+- Rock Slide
+- Body Slam
+- Explosion
+
+Jolteon
+- Thunderbolt
+- Double Kick
+- Thunder Wave
+- Pin Missile
+
+Jynx
+- Lovely Kiss
+- Blizzard
+- Psychic
+- Rest
+
+Lapras
+- Blizzard
+- Thunderbolt
+- Body Slam
+- Confuse Ray
+
+Persian
+- Slash
+- Bubble Beam
+- Thunderbolt
+- Hyper Beam
+
+Rhydon
+- Earthquake
+- Rock Slide
+- Body Slam
+- Substitute
+
+Slowbro
+- Amnesia
+- Surf
+- Thunder Wave
+- Rest
+
+Snorlax
+- Body Slam
+- Reflect
+- Rest
+- Ice Beam
+
+Starmie
+- Psychic
+- Blizzard
+- Thunder Wave
+- Recover
+
+Tauros
+- Body Slam
+- Hyper Beam
+- Blizzard
+- Earthquake
+
+Victreebel
+- Razor Leaf
+- Wrap
+- Stun Spore
+- Hyper Beam
+
+Zapdos
+- Thunderbolt
+- Drill Peck
+- Thunder Wave
+- Agility
+";
+
+/// Parse the first line of a Showdown set, like `Nickname (Species) (M) @ Item`.
+fn parse_showdown_name(line: &str) -> Result<TeamMember, TeamError> {
+    let name = line.split(" @ ").next().unwrap().trim();
+    let name = name
+        .strip_suffix("(M)")
+        .or_else(|| name.strip_suffix("(F)"))
+        .unwrap_or(name)
+        .trim();
+
+    match name.rfind(" (") {
+        Some(start) if name.ends_with(')') => {
+            let species = find_species(&name[start + 2..name.len() - 1])?;
+            let mut member = TeamMember::new(species);
+            member.nickname = Some(name[..start].trim().to_owned());
+            Ok(member)
+        }
+        _ => Ok(TeamMember::new(find_species(name)?)),
+    }
+}
+
+/// Parse a spread like `252 HP / 2 Atk`, overriding the values in `stats`.
+///
+/// Showdown lists special as SpA and SpD, either one is accepted.
+fn parse_spread(spread: &str, stats: &mut StatSet) -> Option<()> {
+    for part in spread.split('/') {
+        let mut words = part.split_whitespace();
+        let value = words.next()?.parse().ok()?;
+        let stat = match words.next()?.to_ascii_lowercase().as_str() {
+            "hp" => Stat::HP,
+            "atk" => Stat::Attack,
+            "def" => Stat::Defense,
+            "spa" | "spd" | "spc" => Stat::Special,
+            "spe" => Stat::Speed,
+            _ => return None,
+        };
+        stats[usize::from(stat)] = value;
+    }
+
+    Some(())
+}
+
+/// Read the sets of a Showdown export.
+///
+/// Showdown shows DVs as IVs of twice their value, and stat experience as EVs up to 252. Stats
+/// that aren't listed are at their maximum. Items, abilities and natures are ignored.
+pub fn parse_showdown(text: &str) -> Result<Vec<TeamMember>, TeamError> {
+    let mut team = Vec::new();
+    let mut current: Option<TeamMember> = None;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let invalid = || TeamError::InvalidLine(number + 1, line.to_owned());
+
+        if line.is_empty() {
+            team.extend(current.take());
+            continue;
+        } else if line.starts_with("===") {
+            // Team name header, as exported by the teambuilder.
+            continue;
+        }
+
+        let member = match current.as_mut() {
+            Some(member) => member,
+            None => {
+                current = Some(parse_showdown_name(line)?);
+                continue;
+            }
+        };
+
+        if let Some(name) = line.strip_prefix('-') {
+            member.learn(name.trim())?;
+        } else if let Some(level) = line.strip_prefix("Level:") {
+            member.set_level(level.trim().parse().map_err(|_| invalid())?)?;
+        } else if let Some(spread) = line.strip_prefix("IVs:") {
+            let mut ivs = [30; 5];
+            parse_spread(spread, &mut ivs).ok_or_else(invalid)?;
+            member.set_ivs(ivs.map(|iv| iv.min(31) / 2));
+        } else if let Some(spread) = line.strip_prefix("EVs:") {
+            let mut evs = [252; 5];
+            parse_spread(spread, &mut evs).ok_or_else(invalid)?;
+            // The stat calculation uses the square root of the stat experience.
+            member.evs = evs.map(|ev| ev.min(255).pow(2));
+        } else if !line.contains(':') && !line.ends_with("Nature") {
+            return Err(invalid());
+        }
+    }
+
+    team.extend(current);
+    Ok(team)
+}
+
+fn default_level() -> u8 {
+    100
+}
+
+/// A team member as stored in JSON.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonMember {
+    species: String,
+    nickname: Option<String>,
+    #[serde(default = "default_level")]
+    level: u8,
+    /// DVs for attack, defense, special and speed.
+    dvs: Option<[u16; 4]>,
+    /// Stat experience for HP, attack, defense, special and speed.
+    stat_exp: Option<StatSet>,
+    moves: Vec<String>,
+}
+
+/// Read sets from JSON.
+///
+/// The sets are a list of objects with a `species` and `moves`, and optionally a `nickname`, a
+/// `level`, `dvs` for attack, defense, special and speed, and `stat_exp`.
+pub fn parse_json(text: &str) -> Result<Vec<TeamMember>, TeamError> {
+    let members: Vec<JsonMember> = serde_json::from_str(text)?;
+    members
+        .into_iter()
+        .map(|json| {
+            let mut member = TeamMember::new(find_species(&json.species)?);
+            member.nickname = json.nickname;
+            member.set_level(json.level)?;
+            if let Some([attack, defense, special, speed]) = json.dvs {
+                member.set_ivs([0, attack, defense, special, speed].map(|dv| dv.min(15)));
+            }
+            if let Some(stat_exp) = json.stat_exp {
+                member.evs = stat_exp;
+            }
+            for name in &json.moves {
+                member.learn(name)?;
+            }
+            Ok(member)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_showdown() {
+        let text = "=== [gen1ou] Example ===
+
+Bull (Tauros) (M) @ Leftovers
+Level: 50
+IVs: 28 Def
+EVs: 0 HP
+- Body Slam
+- Hyper Beam
+
+Nidoran-F
+- Double Kick
+";
+        let team = parse(text).unwrap();
+        assert_eq!(2, team.len());
+
+        let tauros = &team[0];
+        assert_eq!("Tauros", tauros.species.name);
+        assert_eq!("Bull", tauros.name());
+        assert_eq!(50, tauros.level);
+        assert_eq!([11, 15, 14, 15, 15], tauros.ivs);
+        assert_eq!([0, 63504, 63504, 63504, 63504], tauros.evs);
+        assert_eq!(2, tauros.moves.len());
+
+        assert_eq!("Nidoran♀", team[1].name());
+        assert_eq!(
+            "Double Kick",
+            team[1].pokemon().moves[0].unwrap().known().name()
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let text = r#"[
+            {"species": "Chansey", "dvs": [14, 15, 15, 15], "moves": ["Soft-Boiled"]},
+            {"species": "Mr. Mime", "level": 60, "moves": []}
+        ]"#;
+        let team = parse(text).unwrap();
+        assert_eq!(7, team[0].ivs[0]);
+        assert_eq!(60, team[1].pokemon().level);
+
+        let unknown = parse(r#"[{"species": "Pikachu", "moves": ["Volt Tackle"]}]"#);
+        assert!(matches!(unknown, Err(TeamError::UnknownMove(_))));
+        assert!(matches!(parse("[]"), Err(TeamError::Empty)));
+        assert!(matches!(
+            parse("Missingno"),
+            Err(TeamError::UnknownSpecies(_))
+        ));
+    }
+
+    #[test]
+    fn test_ou_sets() {
+        let sets = parse_sets(OU_SETS).unwrap();
+        assert_eq!(17, sets.len());
+        assert!(sets.iter().all(|set| set.moves.len() == 4));
+        assert!(matches!(parse(OU_SETS), Err(TeamError::TooManyMembers)));
+    }
+}
@@ -2,7 +2,7 @@ use num::rational::Ratio;
 use num::traits::identities::{One, Zero};
 
 /// Pokémon or move type.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Type {
     Normal,
     Fighting,
@@ -140,10 +140,10 @@ impl Type {
 
     pub fn is_physical(self) -> bool {
         use self::Type::*;
-        match self {
-            Normal | Fighting | Flying | Poison | Ground | Rock | Bug | Ghost => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            Normal | Fighting | Flying | Poison | Ground | Rock | Bug | Ghost
+        )
     }
 
     pub fn is_special(self) -> bool {
@@ -169,7 +169,7 @@ mod tests {
 
     #[test]
     fn test_is_physical() {
-        assert_eq!(true, Type::Normal.is_physical());
-        assert_eq!(false, Type::Normal.is_special());
+        assert!(Type::Normal.is_physical());
+        assert!(!Type::Normal.is_special());
     }
 }
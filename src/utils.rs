@@ -39,6 +39,21 @@ where
     }
 }
 
+/// Normalize the name of a species or move for lookups.
+///
+/// The result is lowercase, without spaces and punctuation. The gender symbols of the Nidoran
+/// become "f" and "m".
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '♀' => 'f',
+            '♂' => 'm',
+            c => c.to_ascii_lowercase(),
+        })
+        .filter(char::is_ascii_alphanumeric)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,12 +62,12 @@ mod tests {
     fn test_isqrt() {
         // Ensure that it works across the entire number u16 range.
         for n in 0u32..(1 << 16) {
-            let root = n.isqrt();
+            let root = IntegerSquareRoot::isqrt(n);
             assert!(root * root <= n);
             assert!((root + 1) * (root + 1) > n);
         }
 
         // Ensure that the optimized code path also works
-        assert_eq!(15, 255u8.isqrt())
+        assert_eq!(15, IntegerSquareRoot::isqrt(255u8))
     }
 }
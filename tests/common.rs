@@ -8,6 +8,7 @@ pub const MEW: Pokemon = Pokemon {
     evs: PERFECT_EVS,
     ivs: PERFECT_IVS,
     types: [Some(Type::Psychic), None],
+    moves: [None; 4],
 };
 
 pub const MEWTWO: Pokemon = Pokemon {
@@ -16,4 +17,5 @@ pub const MEWTWO: Pokemon = Pokemon {
     evs: PERFECT_EVS,
     ivs: PERFECT_IVS,
     types: [Some(Type::Psychic), None],
+    moves: [None; 4],
 };
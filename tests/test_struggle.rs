@@ -27,7 +27,7 @@ fn struggle_battle() {
         // Mewtwo is faster, so move it first.
         println!("Mewtwo used struggle.");
         if struggle.hits(&mut rng, mewtwo.get_modifier(Accuracy), mew.get_modifier(Evasion)) {
-            let damage = struggle.damage(&mut rng, &mewtwo, &mew);
+            let damage = struggle.damage(&mut rng, &mewtwo, &mew, false);
 
             mew.damage(damage);
             println!("Mew took {} damage", damage);
@@ -47,7 +47,7 @@ fn struggle_battle() {
         // Mewtwo is faster, so move it first.
         println!("Mew used struggle.");
         if struggle.hits(&mut rng, mew.get_modifier(Accuracy), mewtwo.get_modifier(Evasion)) {
-            let damage = struggle.damage(&mut rng, &mew, &mewtwo);
+            let damage = struggle.damage(&mut rng, &mew, &mewtwo, false);
 
             mewtwo.damage(damage);
             println!("Mewtwo took {} damage", damage);
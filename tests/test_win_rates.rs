@@ -0,0 +1,30 @@
+use genwunsym::player::{GreedyPlayer, Player};
+use genwunsym::simulation::{win_rates, Simulation};
+
+use crate::common::{MEW, MEWTWO};
+
+mod common;
+
+/// Put numbers on the struggle battle between Mewtwo and Mew.
+///
+/// Mewtwo wins or it is a draw most of the time, Mew only wins with the help of critical hits.
+#[test]
+fn struggle_win_rates() {
+    let config = Simulation {
+        battles: 1000,
+        seed: 42,
+        ..Default::default()
+    };
+    let players = |_, _| -> Box<dyn Player> { Box::new(GreedyPlayer) };
+    let results = win_rates(&[MEWTWO], &[MEW], players, &config);
+
+    println!("Mewtwo wins: {:?}", results.win_rate(0));
+    println!("Mew wins: {:?}", results.win_rate(1));
+    println!("Draws: {:?}", results.draw_rate());
+    println!("Average turns: {}", results.average_turns());
+
+    assert_eq!(0, results.timeouts);
+    assert!(results.win_rate(1).upper < 0.1);
+    assert!(results.win_rate(1).upper < results.draw_rate().lower);
+    assert!(results.draw_rate().upper < results.win_rate(0).lower);
+}